@@ -0,0 +1,39 @@
+//! Fallback chains.
+//!
+//! The `Alternative` trait chains carriers of the same type so that the
+//! first one holding a value wins.  The alternatives are evaluated
+//! lazily:
+//!
+//! ```rust
+//! use carrier::Alternative;
+//!
+//! fn from_cache(key: &str) -> Option<String> { None }
+//! fn from_disk(key: &str) -> Option<String> { Some(format!("disk:{}", key)) }
+//!
+//! let value = from_cache("a").alt(|| from_disk("a"));
+//! assert_eq!(value.as_ref().map(|x| &x[..]), Some("disk:a"));
+//! ```
+//!
+//! The `first_ok!` macro does the same for carriers of different types
+//! and propagates the failure of the last one from the function.
+use {Branch, Completion, FromValue};
+
+/// A carrier that can fall back to an alternative.
+///
+/// This is implemented for every carrier that implements `Branch` and
+/// can be rebuilt from its value with `FromValue`.
+pub trait Alternative: Sized {
+    /// Returns `self` if it holds a value, otherwise the alternative.
+    fn alt<F: FnOnce() -> Self>(self, alternative: F) -> Self;
+}
+
+impl<T> Alternative for T
+    where T: Branch + FromValue<<T as Branch>::Value>
+{
+    fn alt<F: FnOnce() -> T>(self, alternative: F) -> T {
+        match self.branch() {
+            Completion::Value(value) => T::from_value(value),
+            Completion::Abrupt(..) => alternative(),
+        }
+    }
+}
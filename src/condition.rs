@@ -0,0 +1,150 @@
+//! Restartable conditions.
+//!
+//! With the `conditions` feature enabled a caller can install a handler
+//! for an error type that is consulted by every `try!(?cond expr)` deeper
+//! in the stack before it propagates such an error.  The handler decides
+//! whether the error propagates as usual or whether the `try!` invocation
+//! continues with a substitute value instead.  This lets a top-level
+//! policy decide how to recover without threading flags through every
+//! function:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! use carrier::condition::{self, Condition, Restart};
+//!
+//! #[derive(Debug)]
+//! struct Malformed(String);
+//!
+//! impl Condition for Malformed {}
+//!
+//! fn parse_record(line: &str) -> Result<Option<i32>, Malformed> {
+//!     line.parse().map(Some).map_err(|_| Malformed(line.into()))
+//! }
+//!
+//! fn load(lines: &[&str]) -> Result<Vec<i32>, Malformed> {
+//!     let mut rv = Vec::new();
+//!     for line in lines {
+//!         rv.extend(try!(?cond parse_record(line)));
+//!     }
+//!     Ok(rv)
+//! }
+//!
+//! # fn main() {
+//! assert!(load(&["1", "x", "3"]).is_err());
+//!
+//! let _guard = condition::handle(|_: &Malformed| Restart::Use(None::<i32>));
+//! assert_eq!(load(&["1", "x", "3"]).unwrap(), vec![1, 3]);
+//! # }
+//! ```
+//!
+//! Conditions are only signalled by the `try!(?cond expr)` form, plain
+//! `try!` invocations never consult handlers.  The expression has to be a
+//! `Result` whose error type implements the `Condition` marker trait.
+//!
+//! **A handler only applies to the invocations whose value type is
+//! exactly the type of the substitute it returns.**  The handlers are
+//! looked up by the error type and that value type at runtime, so a
+//! handler returning `Restart::Use(0i64)` is never consulted by an
+//! invocation producing an `i32`, and the error propagates as if no
+//! handler was installed.  This is not detected at compile time, so
+//! annotate the substitute like `Restart::Use(None::<i32>)` above when
+//! its type is not obvious.  The value type of such invocations has to
+//! be `'static`.
+//!
+//! Handlers are registered for the current thread only and stay active
+//! until the returned `HandlerGuard` is dropped.
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+/// Marks an error type as a condition that handlers can recover from.
+pub trait Condition: 'static {}
+
+/// The decision of a condition handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Restart<V> {
+    /// Propagates the error from the `try!` invocation.
+    Propagate,
+    /// Continues the `try!` invocation with the given value.
+    Use(V),
+}
+
+type Handler<E, V> = Box<dyn Fn(&E) -> Restart<V>>;
+
+struct Registration {
+    id: u64,
+    handler: Rc<dyn Any>,
+}
+
+thread_local! {
+    static HANDLERS: RefCell<Vec<Registration>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Installs a handler for errors of type `E`.
+///
+/// The handler is consulted by `try!(?cond expr)` invocations with a
+/// value of type `V` on the current thread until the returned guard is
+/// dropped.  Invocations with any other value type skip the handler, see
+/// the module documentation.  If multiple handlers apply the most recent
+/// one is asked first, and the next one only if it returns
+/// `Restart::Propagate`.
+pub fn handle<E, V, F>(handler: F) -> HandlerGuard
+    where E: Condition, V: 'static, F: Fn(&E) -> Restart<V> + 'static
+{
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    let handler: Handler<E, V> = Box::new(handler);
+    HANDLERS.with(|handlers| {
+        handlers.borrow_mut().push(Registration {
+            id,
+            handler: Rc::new(handler),
+        });
+    });
+    HandlerGuard { id }
+}
+
+/// Asks the handlers for a substitute value for the error.
+pub(crate) fn signal<E: Condition, V: 'static>(error: &E) -> Option<V> {
+    let handlers = HANDLERS.try_with(|handlers| {
+        handlers.borrow().iter().rev()
+            .filter(|registration| registration.handler.is::<Handler<E, V>>())
+            .map(|registration| registration.handler.clone())
+            .collect()
+    }).unwrap_or_else(|_| Vec::new());
+    for handler in handlers {
+        let handler = handler.downcast_ref::<Handler<E, V>>().unwrap();
+        if let Restart::Use(value) = handler(error) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Keeps a condition handler installed.
+///
+/// The handler is removed when the guard is dropped.  The guard is bound
+/// to the thread that installed the handler.
+#[must_use = "the handler is removed when the guard is dropped"]
+pub struct HandlerGuard {
+    id: u64,
+}
+
+impl fmt::Debug for HandlerGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HandlerGuard").finish()
+    }
+}
+
+impl Drop for HandlerGuard {
+    fn drop(&mut self) {
+        let id = self.id;
+        HANDLERS.try_with(|handlers| {
+            handlers.borrow_mut().retain(|registration| registration.id != id);
+        }).ok();
+    }
+}
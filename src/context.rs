@@ -0,0 +1,118 @@
+//! Errors with attached context messages.
+//!
+//! The `Context` type is produced by the context form of the `try!`
+//! macro: `try!(expr; "loading config {}", path)`.  When an error passes
+//! through multiple such `try!` invocations the messages are stacked on
+//! top of each other, so the error carries a breadcrumb trail of what was
+//! being attempted.  Each message is a link in the chain exposed by
+//! `std::error::Error::source`, which terminates in the original error.
+use std::error::Error;
+use std::fmt;
+
+/// An error wrapped with a context message.
+///
+/// The outermost message is the one displayed, the inner messages and
+/// eventually the original error are reachable through `source()`.
+#[derive(Debug)]
+pub struct Context<E> {
+    message: String,
+    inner: Inner<E>,
+}
+
+#[derive(Debug)]
+enum Inner<E> {
+    Error(E),
+    Context(Box<Context<E>>),
+}
+
+/// An iterator over the messages of a `Context`.
+///
+/// This is returned by `Context::messages`.
+pub struct Messages<'a, E: 'a> {
+    next: Option<&'a Context<E>>,
+}
+
+impl<E> Context<E> {
+    /// Wraps an error with a context message.
+    pub fn new<M: Into<String>>(error: E, message: M) -> Context<E> {
+        Context {
+            message: message.into(),
+            inner: Inner::Error(error),
+        }
+    }
+
+    /// Wraps an already wrapped error with another context message.
+    ///
+    /// Unlike `Context::new` this does not nest the types, the message
+    /// is pushed onto the existing trail instead.
+    pub fn wrap<M: Into<String>>(self, message: M) -> Context<E> {
+        Context {
+            message: message.into(),
+            inner: Inner::Context(Box::new(self)),
+        }
+    }
+
+    /// Returns the outermost context message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Iterates over all context messages from the outermost to the
+    /// innermost one.
+    pub fn messages(&self) -> Messages<'_, E> {
+        Messages { next: Some(self) }
+    }
+
+    /// Returns a reference to the original error.
+    pub fn error(&self) -> &E {
+        match self.inner {
+            Inner::Error(ref err) => err,
+            Inner::Context(ref ctx) => ctx.error(),
+        }
+    }
+
+    /// Unwraps the context and returns the original error.
+    pub fn into_error(self) -> E {
+        match self.inner {
+            Inner::Error(err) => err,
+            Inner::Context(ctx) => ctx.into_error(),
+        }
+    }
+}
+
+impl<'a, E> Iterator for Messages<'a, E> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let ctx = self.next.take()?;
+        if let Inner::Context(ref inner) = ctx.inner {
+            self.next = Some(inner);
+        }
+        Some(&ctx.message)
+    }
+}
+
+impl<E> From<Context<Context<E>>> for Context<E> {
+    fn from(ctx: Context<Context<E>>) -> Context<E> {
+        let Context { message, inner } = ctx;
+        match inner {
+            Inner::Error(inner) => inner.wrap(message),
+            Inner::Context(outer) => Context::from(*outer).wrap(message),
+        }
+    }
+}
+
+impl<E> fmt::Display for Context<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl<E: Error + 'static> Error for Context<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.inner {
+            Inner::Error(ref err) => Some(err),
+            Inner::Context(ref ctx) => Some(&**ctx),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! A boxed error type for applications.
+//!
+//! `Error` can hold any `std::error::Error + Send + Sync + 'static`.  As
+//! it implements `From` for all of them it can be used as the error type
+//! of functions that propagate different errors with `try!`:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! # use std::collections::HashMap;
+//! fn lookup(map: &HashMap<&str, &str>, key: &str) -> Result<i32, carrier::Error> {
+//!     let raw = try!(map.get(key));
+//!     Ok(try!(raw.parse::<i32>()))
+//! }
+//!
+//! # fn main() {
+//! let mut map = HashMap::new();
+//! map.insert("answer", "x");
+//! let err = lookup(&map, "answer").unwrap_err();
+//! assert!(err.is::<std::num::ParseIntError>());
+//! assert!(lookup(&map, "question").unwrap_err().is::<carrier::NoneError>());
+//! # }
+//! ```
+//!
+//! A backtrace is captured when the error is created.  Whether it's
+//! actually recorded is controlled by the `RUST_BACKTRACE` and
+//! `RUST_LIB_BACKTRACE` environment variables as with
+//! `std::backtrace::Backtrace::capture`.
+//!
+//! To allow the blanket `From` implementation, `Error` does not implement
+//! `std::error::Error` itself.  It dereferences to the wrapped error
+//! instead and converts into a boxed error.
+use std::backtrace::Backtrace;
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::Deref;
+
+/// A boxed dynamic error with a backtrace.
+pub struct Error {
+    inner: Box<Inner>,
+}
+
+struct Inner {
+    error: Box<dyn StdError + Send + Sync>,
+    backtrace: Backtrace,
+}
+
+/// An iterator over an error and its sources.
+///
+/// This is returned by `Error::chain`.
+pub struct Chain<'a> {
+    next: Option<&'a (dyn StdError + 'static)>,
+}
+
+impl Error {
+    /// Wraps an error and captures a backtrace.
+    pub fn new<E>(error: E) -> Error
+        where E: StdError + Send + Sync + 'static
+    {
+        Error::from_boxed(Box::new(error))
+    }
+
+    /// Wraps an already boxed error and captures a backtrace.
+    pub fn from_boxed(error: Box<dyn StdError + Send + Sync>) -> Error {
+        Error {
+            inner: Box::new(Inner {
+                error,
+                backtrace: Backtrace::capture(),
+            }),
+        }
+    }
+
+    /// The backtrace captured when the error was created.
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.inner.backtrace
+    }
+
+    /// Returns `true` if the wrapped error is of type `E`.
+    pub fn is<E: StdError + 'static>(&self) -> bool {
+        self.inner.error.is::<E>()
+    }
+
+    /// Returns a reference to the wrapped error if it's of type `E`.
+    pub fn downcast_ref<E: StdError + 'static>(&self) -> Option<&E> {
+        self.inner.error.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the wrapped error if it's of type `E`.
+    pub fn downcast_mut<E: StdError + 'static>(&mut self) -> Option<&mut E> {
+        self.inner.error.downcast_mut()
+    }
+
+    /// Unwraps the error if it's of type `E`.
+    ///
+    /// Otherwise the error is returned unchanged.
+    pub fn downcast<E: StdError + 'static>(self) -> Result<E, Error> {
+        let Inner { error, backtrace } = *self.inner;
+        match error.downcast() {
+            Ok(error) => Ok(*error),
+            Err(error) => Err(Error {
+                inner: Box::new(Inner { error, backtrace }),
+            }),
+        }
+    }
+
+    /// Iterates over the wrapped error and its sources.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain {
+            next: Some(&*self.inner.error),
+        }
+    }
+
+    /// The last error in the source chain.
+    pub fn root_cause(&self) -> &(dyn StdError + 'static) {
+        self.chain().last().unwrap()
+    }
+
+    /// Unwraps the boxed error and discards the backtrace.
+    pub fn into_inner(self) -> Box<dyn StdError + Send + Sync> {
+        self.inner.error
+    }
+}
+
+impl<E> From<E> for Error
+    where E: StdError + Send + Sync + 'static
+{
+    fn from(error: E) -> Error {
+        Error::new(error)
+    }
+}
+
+impl From<Error> for Box<dyn StdError + Send + Sync> {
+    fn from(error: Error) -> Box<dyn StdError + Send + Sync> {
+        error.into_inner()
+    }
+}
+
+impl From<Error> for Box<dyn StdError> {
+    fn from(error: Error) -> Box<dyn StdError> {
+        error.into_inner()
+    }
+}
+
+impl Deref for Error {
+    type Target = dyn StdError + Send + Sync;
+
+    fn deref(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.inner.error
+    }
+}
+
+impl AsRef<dyn StdError + Send + Sync> for Error {
+    fn as_ref(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.inner.error
+    }
+}
+
+impl AsRef<dyn StdError> for Error {
+    fn as_ref(&self) -> &(dyn StdError + 'static) {
+        &*self.inner.error
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.inner.error, f)
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner.error, f)
+    }
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn StdError + 'static);
+
+    fn next(&mut self) -> Option<&'a (dyn StdError + 'static)> {
+        let rv = self.next.take()?;
+        self.next = rv.source();
+        Some(rv)
+    }
+}
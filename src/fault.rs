@@ -0,0 +1,203 @@
+//! Fault injection at `try!` invocations.
+//!
+//! With the `fault-injection` feature enabled tests can force `try!`
+//! invocations to complete abruptly without having to construct failing
+//! inputs.  Only invocations with a label given as `try!(#"label" expr)`
+//! take part, they are selected either by their label or by their
+//! location:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! # use std::io;
+//! use carrier::fault;
+//!
+//! #[derive(Debug)]
+//! enum DbError {
+//!     Io(io::Error),
+//! }
+//!
+//! impl From<io::Error> for DbError {
+//!     fn from(err: io::Error) -> DbError { DbError::Io(err) }
+//! }
+//!
+//! fn read_row() -> Result<String, io::Error> {
+//!     Ok("row".into())
+//! }
+//!
+//! fn load() -> Result<String, DbError> {
+//!     Ok(try!(#"db-read" read_row()))
+//! }
+//!
+//! # fn main() {
+//! let guard = fault::inject(fault::label("db-read"), |_site| {
+//!     io::Error::new(io::ErrorKind::Other, "disk on fire")
+//! });
+//! assert!(load().is_err());
+//! assert_eq!(guard.hits(), 1);
+//! drop(guard);
+//! assert!(load().is_ok());
+//! # }
+//! ```
+//!
+//! The factory builds the error of the expression passed to `try!`: the
+//! expression is evaluated as usual and its result is then replaced with
+//! the injected error, which is propagated through the regular
+//! `IntoCompletion` rules.  For an `Option` the factory has to build a
+//! `NoneError` and the result is replaced with `None`.
+//!
+//! A selected invocation panics if the factory builds an error of another
+//! type or if the expression is not a `Result` or `Option`.  While this
+//! feature is enabled the error type of a `Result` passed to a labeled
+//! `try!` has to be `'static`.  Invocations without a label are expanded
+//! exactly as without the feature.
+//!
+//! Injections are registered for the current thread only and stay active
+//! until the returned `FaultGuard` is dropped, so tests running in
+//! parallel do not affect each other.
+use std::any::{self, Any};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use CallSite;
+
+/// Selects the `try!` invocations a fault is injected into.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Selector {
+    /// Matches labeled invocations in the given file (as in `file!()`)
+    /// and line.
+    Location(String, u32),
+    /// Matches invocations with the given label.
+    Label(String),
+}
+
+/// Selects the labeled `try!` invocations in the given file and line.
+pub fn at<F: Into<String>>(file: F, line: u32) -> Selector {
+    Selector::Location(file.into(), line)
+}
+
+/// Selects the `try!` invocations with the given label.
+pub fn label<L: Into<String>>(label: L) -> Selector {
+    Selector::Label(label.into())
+}
+
+impl Selector {
+    /// Returns `true` if the selector matches the call site.
+    pub fn matches(&self, site: &CallSite) -> bool {
+        match *self {
+            Selector::Location(ref file, line) => {
+                site.file() == file && site.line() == line
+            }
+            Selector::Label(ref label) => site.label() == Some(label),
+        }
+    }
+}
+
+type Factory = Rc<dyn Fn(&'static CallSite) -> Box<dyn Any>>;
+
+struct Injection {
+    id: u64,
+    selector: Selector,
+    factory: Factory,
+    error_type: &'static str,
+    hits: Rc<Cell<usize>>,
+}
+
+thread_local! {
+    static INJECTIONS: RefCell<Vec<Injection>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Injects faults into the selected `try!` invocations.
+///
+/// Every time a selected invocation is evaluated on the current thread
+/// the factory is called to build the error of its expression.  The
+/// injection is active until the returned guard is dropped.  If multiple
+/// injections select an invocation the most recent one wins.
+pub fn inject<F, E>(selector: Selector, factory: F) -> FaultGuard
+    where F: Fn(&'static CallSite) -> E + 'static, E: 'static
+{
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    let hits = Rc::new(Cell::new(0));
+    INJECTIONS.with(|injections| {
+        injections.borrow_mut().push(Injection {
+            id,
+            selector,
+            factory: Rc::new(move |site| Box::new(factory(site))),
+            error_type: any::type_name::<E>(),
+            hits: hits.clone(),
+        });
+    });
+    FaultGuard { id, hits }
+}
+
+/// Finds the injection selecting the call site and counts the hit.
+fn select(site: &'static CallSite) -> Option<(Factory, &'static str)> {
+    INJECTIONS.try_with(|injections| {
+        let injections = injections.borrow();
+        injections.iter().rev()
+            .find(|injection| injection.selector.matches(site))
+            .map(|injection| {
+                injection.hits.set(injection.hits.get() + 1);
+                (injection.factory.clone(), injection.error_type)
+            })
+    }).ok().and_then(|x| x)
+}
+
+/// Builds the injected error for the call site if it's selected.
+///
+/// Panics if the injection builds an error of another type.
+pub(crate) fn take<E: 'static>(site: &'static CallSite) -> Option<E> {
+    let (factory, error_type) = select(site)?;
+    match factory(site).downcast::<E>() {
+        Ok(error) => Some(*error),
+        Err(_) => panic!("cannot inject a fault of type `{}` into the try! at {} which \
+                          fails with `{}`", error_type, site, any::type_name::<E>()),
+    }
+}
+
+/// Panics if the call site of an expression that cannot fail is selected.
+pub(crate) fn reject(site: &'static CallSite, source_type: &'static str) {
+    if let Some((_, error_type)) = select(site) {
+        panic!("cannot inject a fault of type `{}` into the try! at {} on a `{}`",
+               error_type, site, source_type);
+    }
+}
+
+/// Keeps an injection active.
+///
+/// The injection is removed when the guard is dropped.  The guard is
+/// bound to the thread that registered the injection.
+#[must_use = "the injection is removed when the guard is dropped"]
+pub struct FaultGuard {
+    id: u64,
+    hits: Rc<Cell<usize>>,
+}
+
+impl FaultGuard {
+    /// How often a fault was injected.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+}
+
+impl fmt::Debug for FaultGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FaultGuard")
+            .field("hits", &self.hits())
+            .finish()
+    }
+}
+
+impl Drop for FaultGuard {
+    fn drop(&mut self) {
+        let id = self.id;
+        INJECTIONS.try_with(|injections| {
+            injections.borrow_mut().retain(|injection| injection.id != id);
+        }).ok();
+    }
+}
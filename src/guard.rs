@@ -0,0 +1,67 @@
+//! Cleanup that only runs when a scope is left early.
+//!
+//! A `Transaction` runs its rollback when it's dropped without having
+//! been committed.  The success path commits it, so the rollback only
+//! runs if the scope is left through a `try!`, a `return` or a panic
+//! before reaching the commit:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! # use std::cell::Cell;
+//! use carrier::Transaction;
+//!
+//! fn reserve(counter: &Cell<i32>, amount: &str) -> Result<i32, std::num::ParseIntError> {
+//!     counter.set(counter.get() + 1);
+//!     let tx = Transaction::new(|| counter.set(counter.get() - 1));
+//!     let amount = try!(amount.parse::<i32>());
+//!     tx.commit();
+//!     Ok(amount)
+//! }
+//!
+//! # fn main() {
+//! let counter = Cell::new(0);
+//! assert!(reserve(&counter, "x").is_err());
+//! assert_eq!(counter.get(), 0);
+//! assert_eq!(reserve(&counter, "42"), Ok(42));
+//! assert_eq!(counter.get(), 1);
+//! # }
+//! ```
+//!
+//! The `on_abrupt!` and `on_value!` macros wrap a block so that a
+//! closure runs only if the block is left early or only if it completes
+//! normally.  `defer_err!` is an alias for `on_abrupt!`.
+use std::fmt;
+
+/// A guard that rolls back unless it's committed.
+#[must_use = "the rollback runs immediately if the transaction is dropped"]
+pub struct Transaction<F: FnOnce()> {
+    rollback: Option<F>,
+}
+
+impl<F: FnOnce()> Transaction<F> {
+    /// Creates a transaction with the given rollback.
+    pub fn new(rollback: F) -> Transaction<F> {
+        Transaction {
+            rollback: Some(rollback),
+        }
+    }
+
+    /// Commits the transaction so that the rollback never runs.
+    pub fn commit(mut self) {
+        self.rollback = None;
+    }
+}
+
+impl<F: FnOnce()> fmt::Debug for Transaction<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Transaction").finish()
+    }
+}
+
+impl<F: FnOnce()> Drop for Transaction<F> {
+    fn drop(&mut self) {
+        if let Some(rollback) = self.rollback.take() {
+            rollback();
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Hooks invoked when `try!` propagates an abrupt completion.
+//!
+//! With the `hooks` feature enabled a single global hook can be
+//! registered with `set_abrupt_hook`.  It is called by every `try!` that
+//! takes the abrupt branch, with the call site and a `Debug` view of the
+//! residual of the expression, before it is converted into the return
+//! value:
+//!
+//! ```rust
+//! # extern crate carrier;
+//! use std::fmt::Debug;
+//! use carrier::CallSite;
+//!
+//! fn print_hook(site: &'static CallSite, abrupt: &dyn Debug) {
+//!     eprintln!("{:?} propagated at {}", abrupt, site);
+//! }
+//!
+//! # fn main() {
+//! carrier::set_abrupt_hook(print_hook);
+//! # }
+//! ```
+//!
+//! Residuals that do not implement `Debug` and expressions with a custom
+//! `IntoCompletion` implementation are shown as the type name of the
+//! expression in angle brackets.  While no hook is registered the hook
+//! costs a single atomic load on the abrupt branch.
+//!
+//! The `log` and `tracing` features provide ready-made hooks that emit a
+//! debug event for every propagation: `log_hook` and `tracing_hook`.
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use CallSite;
+
+/// The signature of an abrupt hook.
+pub type AbruptHook = fn(&'static CallSite, &dyn fmt::Debug);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers the global abrupt hook.
+///
+/// This replaces the previously registered hook which is returned.
+pub fn set_abrupt_hook(hook: AbruptHook) -> Option<AbruptHook> {
+    from_ptr(HOOK.swap(hook as *mut (), Ordering::AcqRel))
+}
+
+/// Unregisters the global abrupt hook and returns it.
+pub fn take_abrupt_hook() -> Option<AbruptHook> {
+    from_ptr(HOOK.swap(ptr::null_mut(), Ordering::AcqRel))
+}
+
+/// Returns the currently registered abrupt hook.
+pub fn abrupt_hook() -> Option<AbruptHook> {
+    from_ptr(HOOK.load(Ordering::Acquire))
+}
+
+fn from_ptr(ptr: *mut ()) -> Option<AbruptHook> {
+    if ptr.is_null() {
+        None
+    } else {
+        // only ever stores null or a valid `AbruptHook`
+        Some(unsafe { mem::transmute::<*mut (), AbruptHook>(ptr) })
+    }
+}
+
+/// A hook that logs propagations at debug level with `log`.
+///
+/// The log target is the module path of the `try!` invocation.
+#[cfg(feature = "log")]
+pub fn log_hook(site: &'static CallSite, abrupt: &dyn fmt::Debug) {
+    ::log::debug!(target: site.module_path(), "abrupt completion at {}: {:?}", site, abrupt);
+}
+
+/// A hook that emits a debug event for propagations with `tracing`.
+#[cfg(feature = "tracing")]
+pub fn tracing_hook(site: &'static CallSite, abrupt: &dyn fmt::Debug) {
+    ::tracing::debug!(
+        file = site.file(),
+        line = site.line(),
+        column = site.column(),
+        module_path = site.module_path(),
+        abrupt = ?abrupt,
+        "abrupt completion"
+    );
+}
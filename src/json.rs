@@ -0,0 +1,42 @@
+//! Helpers to write JSON documents.
+use std::fmt::Write;
+
+use CallSite;
+
+/// Writes a JSON string literal.
+pub fn write_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes an optional JSON string literal.
+pub fn write_opt_str(out: &mut String, value: Option<&str>) {
+    match value {
+        Some(value) => write_str(out, value),
+        None => out.push_str("null"),
+    }
+}
+
+/// Writes the fields of a call site without the surrounding braces.
+pub fn write_site_fields(out: &mut String, site: &CallSite) {
+    out.push_str("\"file\":");
+    write_str(out, site.file());
+    write!(out, ",\"line\":{},\"column\":{},\"module_path\":",
+           site.line(), site.column()).unwrap();
+    write_str(out, site.module_path());
+    out.push_str(",\"expression\":");
+    write_opt_str(out, site.expression());
+    out.push_str(",\"label\":");
+    write_opt_str(out, site.label());
+}
@@ -64,6 +64,15 @@
 //! `IntoCompletion` trait which defines the transition rules for how
 //! to create a completion from `A` to `B`.
 //!
+//! The builtin rules are not implemented on `IntoCompletion` directly but
+//! are split into two halves.  The `Branch` trait takes the source carrier
+//! apart into its value or a *residual* (for instance
+//! `Result<Infallible, E>` for a result), and the `FromResidual` trait on
+//! the target builds the return value from that residual.  A blanket
+//! implementation of `IntoCompletion` connects the two, so every carrier
+//! implementing `Branch` automatically works with every target that
+//! accepts its residual.
+//!
 //! ## Builtin Rules
 //!
 //! The following basic transitions are provided automatically:
@@ -89,6 +98,12 @@
 //! > For the raw pointer versions the null pointer is converted into
 //! > `None` whereas all other values are unwrapped unchanged.
 //!
+//! `Option<U>` -> `Result<T, F>`:
+//! > This conversion propagates `None` as a `NoneError` into functions
+//! > returning results.  It's only available if `NoneError: Into<F>` so
+//! > error types opt into it by implementing `From<NoneError>`.  This
+//! > also applies to null raw pointers.
+//!
 //! ## Custom Rules
 //!
 //! If you have a similar object you want to convert automatically
@@ -127,19 +142,158 @@
 //! This allows the rule to be utilized in a function that returns a
 //! different kind of error that however is compatible to the `ErrorKind`
 //! of that library.
+//!
+//! If the object behaves like a result in general it's often simpler to
+//! implement `Branch` instead and reuse the residual of an existing
+//! carrier.  This makes the object work with every target which accepts
+//! that residual:
+//!
+//! ```rust
+//! # struct Response;
+//! # impl Response {
+//! #     fn is_successful(&self) -> bool { true }
+//! #     fn status(&self) -> i32 { 200 }
+//! # }
+//! # enum ErrorKind { RequestFailed(i32) }
+//! # use std::convert::Infallible;
+//! # use carrier::{Branch, Completion};
+//! impl Branch for Response {
+//!     type Value = Response;
+//!     type Residual = Result<Infallible, ErrorKind>;
+//!
+//!     fn branch(self) -> Completion<Response, Self::Residual> {
+//!         if self.is_successful() {
+//!             Completion::Value(self)
+//!         } else {
+//!             Completion::Abrupt(Err(ErrorKind::RequestFailed(
+//!                 self.status())))
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Because of the blanket implementation a custom rule can no longer be
+//! written as `IntoCompletion` for a source that implements `Branch`.
+//! For instance a rule converting any result into a custom response type
+//! like `impl<T, E> IntoCompletion<MyResponse> for Result<T, E>` now
+//! fails to compile with a conflicting implementation error (E0119).
+//! Such rules have to be expressed on the target instead by implementing
+//! `FromResidual` for the residual of the source:
+//!
+//! ```rust
+//! # use std::convert::Infallible;
+//! # use std::fmt::Display;
+//! # use carrier::FromResidual;
+//! struct MyResponse {
+//!     status: i32,
+//!     body: String,
+//! }
+//!
+//! impl<E: Display> FromResidual<Result<Infallible, E>> for MyResponse {
+//!     fn from_residual(residual: Result<Infallible, E>) -> MyResponse {
+//!         match residual {
+//!             Ok(never) => match never {},
+//!             Err(err) => MyResponse { status: 500, body: err.to_string() },
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! # Ok-Wrapping
+//!
+//! The opposite direction is covered by the `FromValue` trait and the
+//! `ok!` macro.  They wrap a success value into whatever carrier the
+//! function returns:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! # fn foo() -> Option<i32> { Some(42) }
+//! # fn main() {
+//! fn foo_as_string() -> Option<String> {
+//!     ok!(try!(foo()).to_string())
+//! }
+//! # }
+//! ```
+//!
+//! # Cargo Features
+//!
+//! The following optional features are available:
+//!
+//! * `serde`: implements `Serialize` and `Deserialize` for `Completion`.
+//! * `nightly`: implements the unstable `std::ops::Try` trait for
+//!   `Completion` so it can be used with the `?` operator.  See the
+//!   `nightly` module for details.
+//! * `trace`: records the call sites of `try!` invocations into errors
+//!   wrapped in `trace::Traced`.  See the `trace` module for details.
+//! * `trace-expr`: like `trace` but additionally records the source text
+//!   of the expression passed to `try!`.
+//! * `hooks`: allows registering a global hook with `set_abrupt_hook` that
+//!   is invoked whenever `try!` propagates an abrupt completion.  See the
+//!   `hook` module for details.
+//! * `log`, `tracing`: enable `hooks` and provide ready-made hooks which
+//!   emit debug events with the respective crate.
+//! * `stats`: counts value and abrupt completions per `try!` invocation.
+//!   See the `stats` module for details.
+//! * `fault-injection`: allows tests to force `try!` invocations to
+//!   complete abruptly with an injected error.  See the `fault` module for
+//!   details.
+//! * `conditions`: allows installing handlers that recover from errors
+//!   propagated by `try!(?cond expr)` with a substitute value.  See the
+//!   `condition` module for details.
 
-/// This macro performs error handling through the completion system.
-///
-/// In the future this will be implemented with the `?` operator instead.
-#[macro_export]
-macro_rules! try {
-    ($expr:expr) => {
-        match $crate::IntoCompletion::into_completion($expr) {
-            $crate::Completion::Value(x) => x,
-            $crate::Completion::Abrupt(x) => { return x; }
-        }
-    }
-}
+#![cfg_attr(feature = "nightly", feature(try_trait_v2, try_trait_v2_residual))]
+
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+use std::convert::Infallible;
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::task::Poll;
+
+pub use alternative::Alternative;
+pub use context::Context;
+pub use error::Error;
+pub use guard::Transaction;
+pub use outcome::Outcome;
+#[cfg(feature = "hooks")]
+pub use hook::set_abrupt_hook;
+pub use site::CallSite;
+pub use validated::Validated;
+
+#[macro_use]
+mod macros;
+
+pub mod alternative;
+#[cfg(feature = "conditions")]
+pub mod condition;
+pub mod context;
+pub mod error;
+#[cfg(feature = "fault-injection")]
+pub mod fault;
+pub mod guard;
+#[cfg(feature = "hooks")]
+pub mod hook;
+mod json;
+#[cfg(feature = "nightly")]
+pub mod nightly;
+pub mod outcome;
+pub mod report;
+mod site;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "trace")]
+pub mod trace;
+pub mod validated;
+
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
 
 /// A `Completion` is an internal construct which can hold the result of a
 /// computation that in the context of failure handling.
@@ -150,6 +304,12 @@ macro_rules! try {
 /// an abrupt failure.
 ///
 /// This object is also called a "completion carrier" or "result carrier".
+///
+/// With the `serde` feature enabled completions serialize into an
+/// externally tagged format: `{"value": ..}` or `{"abrupt": ..}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Completion<V, F> {
     /// The completion resulted in a value
     Value(V),
@@ -157,7 +317,316 @@ pub enum Completion<V, F> {
     Abrupt(F),
 }
 
+impl<V, F> Completion<V, F> {
+    /// Returns `true` if the completion is a `Value`.
+    pub fn is_value(&self) -> bool {
+        match *self {
+            Completion::Value(_) => true,
+            Completion::Abrupt(_) => false,
+        }
+    }
+
+    /// Returns `true` if the completion is `Abrupt`.
+    pub fn is_abrupt(&self) -> bool {
+        !self.is_value()
+    }
+
+    /// Converts the completion into an `Option<V>`, discarding an abrupt
+    /// value.
+    pub fn value(self) -> Option<V> {
+        match self {
+            Completion::Value(value) => Some(value),
+            Completion::Abrupt(_) => None,
+        }
+    }
+
+    /// Converts the completion into an `Option<F>`, discarding a
+    /// successful value.
+    pub fn abrupt(self) -> Option<F> {
+        match self {
+            Completion::Value(_) => None,
+            Completion::Abrupt(abrupt) => Some(abrupt),
+        }
+    }
+
+    /// Converts from `&Completion<V, F>` to `Completion<&V, &F>`.
+    pub fn as_ref(&self) -> Completion<&V, &F> {
+        match *self {
+            Completion::Value(ref value) => Completion::Value(value),
+            Completion::Abrupt(ref abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+
+    /// Converts from `&mut Completion<V, F>` to `Completion<&mut V, &mut F>`.
+    pub fn as_mut(&mut self) -> Completion<&mut V, &mut F> {
+        match *self {
+            Completion::Value(ref mut value) => Completion::Value(value),
+            Completion::Abrupt(ref mut abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+
+    /// Maps the value of a completion with the given function, leaving an
+    /// abrupt completion untouched.
+    pub fn map<U, O: FnOnce(V) -> U>(self, op: O) -> Completion<U, F> {
+        match self {
+            Completion::Value(value) => Completion::Value(op(value)),
+            Completion::Abrupt(abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+
+    /// Maps the abrupt value of a completion with the given function,
+    /// leaving a value completion untouched.
+    pub fn map_abrupt<G, O: FnOnce(F) -> G>(self, op: O) -> Completion<V, G> {
+        match self {
+            Completion::Value(value) => Completion::Value(value),
+            Completion::Abrupt(abrupt) => Completion::Abrupt(op(abrupt)),
+        }
+    }
+
+    /// Calls `op` with the value if the completion is a `Value`, otherwise
+    /// returns the abrupt completion.
+    pub fn and_then<U, O>(self, op: O) -> Completion<U, F>
+        where O: FnOnce(V) -> Completion<U, F>
+    {
+        match self {
+            Completion::Value(value) => op(value),
+            Completion::Abrupt(abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+
+    /// Calls `op` with the abrupt value if the completion is `Abrupt`,
+    /// otherwise returns the value completion.
+    pub fn or_else<G, O>(self, op: O) -> Completion<V, G>
+        where O: FnOnce(F) -> Completion<V, G>
+    {
+        match self {
+            Completion::Value(value) => Completion::Value(value),
+            Completion::Abrupt(abrupt) => op(abrupt),
+        }
+    }
+
+    /// Unwraps the value of the completion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the completion is `Abrupt` with a message provided by
+    /// the abrupt value.
+    pub fn unwrap_value(self) -> V
+        where F: fmt::Debug
+    {
+        match self {
+            Completion::Value(value) => value,
+            Completion::Abrupt(abrupt) => panic!(
+                "called `Completion::unwrap_value()` on an `Abrupt` value: {:?}",
+                abrupt),
+        }
+    }
+
+    /// Unwraps the value of the completion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the completion is `Abrupt` with the passed message and
+    /// the abrupt value.
+    pub fn expect_value(self, msg: &str) -> V
+        where F: fmt::Debug
+    {
+        match self {
+            Completion::Value(value) => value,
+            Completion::Abrupt(abrupt) => panic!("{}: {:?}", msg, abrupt),
+        }
+    }
+}
+
+impl<V, F> Completion<V, F> {
+    /// Creates a completion from a result.
+    ///
+    /// `Ok` becomes a `Value` and `Err` becomes `Abrupt`.
+    pub fn from_result(result: Result<V, F>) -> Completion<V, F> {
+        match result {
+            Ok(value) => Completion::Value(value),
+            Err(abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+
+    /// Converts the completion into a result.
+    ///
+    /// A `Value` becomes `Ok` and `Abrupt` becomes `Err`.
+    pub fn into_result(self) -> Result<V, F> {
+        match self {
+            Completion::Value(value) => Ok(value),
+            Completion::Abrupt(abrupt) => Err(abrupt),
+        }
+    }
+
+    /// Creates a completion from a `ControlFlow`.
+    ///
+    /// `Continue` becomes a `Value` and `Break` becomes `Abrupt`.
+    pub fn from_control_flow(flow: ControlFlow<F, V>) -> Completion<V, F> {
+        match flow {
+            ControlFlow::Continue(value) => Completion::Value(value),
+            ControlFlow::Break(abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+
+    /// Converts the completion into a `ControlFlow`.
+    ///
+    /// A `Value` becomes `Continue` and `Abrupt` becomes `Break`.
+    pub fn into_control_flow(self) -> ControlFlow<F, V> {
+        match self {
+            Completion::Value(value) => ControlFlow::Continue(value),
+            Completion::Abrupt(abrupt) => ControlFlow::Break(abrupt),
+        }
+    }
+}
+
+impl<V> Completion<V, ()> {
+    /// Creates a completion from an option.
+    ///
+    /// `Some` becomes a `Value` and `None` becomes `Abrupt(())`.
+    pub fn from_option(option: Option<V>) -> Completion<V, ()> {
+        match option {
+            Some(value) => Completion::Value(value),
+            None => Completion::Abrupt(()),
+        }
+    }
+}
+
+impl<V, F> From<Result<V, F>> for Completion<V, F> {
+    fn from(result: Result<V, F>) -> Completion<V, F> {
+        Completion::from_result(result)
+    }
+}
+
+impl<V, F> From<Completion<V, F>> for Result<V, F> {
+    fn from(completion: Completion<V, F>) -> Result<V, F> {
+        completion.into_result()
+    }
+}
+
+impl<V, F> From<ControlFlow<F, V>> for Completion<V, F> {
+    fn from(flow: ControlFlow<F, V>) -> Completion<V, F> {
+        Completion::from_control_flow(flow)
+    }
+}
+
+impl<V, F> From<Completion<V, F>> for ControlFlow<F, V> {
+    fn from(completion: Completion<V, F>) -> ControlFlow<F, V> {
+        completion.into_control_flow()
+    }
+}
+
+impl<V> From<Option<V>> for Completion<V, ()> {
+    fn from(option: Option<V>) -> Completion<V, ()> {
+        Completion::from_option(option)
+    }
+}
+
+impl<V> From<Completion<V, ()>> for Option<V> {
+    fn from(completion: Completion<V, ()>) -> Option<V> {
+        completion.value()
+    }
+}
+
+impl<V, F> Completion<Option<V>, F> {
+    /// Transposes a completion of an option into an option of a
+    /// completion.
+    ///
+    /// `Value(None)` is mapped to `None`, `Value(Some(v))` to
+    /// `Some(Value(v))` and `Abrupt(f)` to `Some(Abrupt(f))`.
+    pub fn transpose(self) -> Option<Completion<V, F>> {
+        match self {
+            Completion::Value(Some(value)) => Some(Completion::Value(value)),
+            Completion::Value(None) => None,
+            Completion::Abrupt(abrupt) => Some(Completion::Abrupt(abrupt)),
+        }
+    }
+}
+
+impl<V, F> Completion<Completion<V, F>, F> {
+    /// Removes one level of nesting from a completion.
+    pub fn flatten(self) -> Completion<V, F> {
+        match self {
+            Completion::Value(inner) => inner,
+            Completion::Abrupt(abrupt) => Completion::Abrupt(abrupt),
+        }
+    }
+}
+
+impl<V> Completion<V, Infallible> {
+    /// Unwraps the value of a completion that can never be abrupt.
+    ///
+    /// Unlike `unwrap_value` this can never panic.
+    pub fn into_value(self) -> V {
+        match self {
+            Completion::Value(value) => value,
+            Completion::Abrupt(never) => match never {},
+        }
+    }
+}
+
+/// The error produced when `None` is propagated into a `Result`.
+///
+/// Error types that want to accept a `None` from `try!` implement
+/// `From<NoneError>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct NoneError;
+
+impl fmt::Display for NoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected a value but found none")
+    }
+}
+
+impl StdError for NoneError {}
+
+/// A trait to extract the error from a residual.
+///
+/// This is used by the extended forms of `try!` which adjust the error
+/// of an abrupt completion at the call site.
+pub trait ResidualError {
+    /// The error held by the residual
+    type Error;
+
+    /// Converts the residual into its error.
+    fn into_error(self) -> Self::Error;
+}
+
+impl<E> ResidualError for Result<Infallible, E> {
+    type Error = E;
+
+    fn into_error(self) -> E {
+        match self {
+            Ok(never) => match never {},
+            Err(err) => err,
+        }
+    }
+}
+
+impl ResidualError for Option<Infallible> {
+    type Error = NoneError;
+
+    fn into_error(self) -> NoneError {
+        NoneError
+    }
+}
+
+impl<F> ResidualError for Completion<Infallible, F> {
+    type Error = F;
+
+    fn into_error(self) -> F {
+        match self {
+            Completion::Value(never) => match never {},
+            Completion::Abrupt(abrupt) => abrupt,
+        }
+    }
+}
+
 /// A conversion trait to convert an object into a `Completion`.
+///
+/// Every type implementing `Branch` already implements this trait for
+/// all targets accepting its residual, so rules for such types have to
+/// be added with `FromResidual` on the target instead.
 pub trait IntoCompletion<R> {
     /// The value of a completion
     type Value;
@@ -166,36 +635,208 @@ pub trait IntoCompletion<R> {
     fn into_completion(self) -> Completion<Self::Value, R>;
 }
 
-impl<T, U, E, F> IntoCompletion<Result<U, F>> for Result<T, E>
-    where E: Into<F>
+/// An extension trait to use `IntoCompletion` types with `?` on stable.
+///
+/// The `?` operator only understands the standard library carriers.  The
+/// `carry` method converts any `IntoCompletion<R>` type into such a
+/// carrier, where `R` is the return type of the enclosing function:
+///
+/// ```rust
+/// # use carrier::Carry;
+/// fn parse(s: &str) -> Result<i32, std::num::ParseIntError> {
+///     let rv = s.parse::<i32>().carry::<Result<i32, _>>()?;
+///     Ok(rv)
+/// }
+/// # assert_eq!(parse("42"), Ok(42));
+/// ```
+///
+/// The conversion into `R` happens within `carry`, so the value that `?`
+/// eventually returns from the function is exactly what `try!` would
+/// have returned.
+///
+/// Functions returning a `Poll` of a result can carry errors as well:
+///
+/// ```rust
+/// # use std::task::Poll;
+/// # use carrier::Carry;
+/// fn poll_next(s: &str) -> Poll<Option<Result<i32, std::num::ParseIntError>>> {
+///     let rv = s.parse::<i32>().carry::<Poll<Option<Result<i32, _>>>>()?;
+///     Poll::Ready(Some(Ok(rv)))
+/// }
+/// # assert_eq!(poll_next("42"), Poll::Ready(Some(Ok(42))));
+/// # assert!(matches!(poll_next("x"), Poll::Ready(Some(Err(_)))));
+/// ```
+///
+/// An `Option<Result<T, E>>` is only supported as a target for sources
+/// which complete with `None`, since `?` cannot return an error from a
+/// function returning an option.  Use `try!` to propagate errors in
+/// iterators instead:
+///
+/// ```rust,compile_fail
+/// # use carrier::Carry;
+/// fn next(s: &str) -> Option<Result<i32, std::num::ParseIntError>> {
+///     let rv = s.parse::<i32>().carry::<Option<Result<i32, _>>>()?;
+///     Some(Ok(rv))
+/// }
+/// ```
+///
+/// Likewise a `Poll` is only supported as a target for sources which
+/// complete with an error, since `?` cannot return `Poll::Pending`.  A
+/// carrier that converts into `Poll::Pending` cannot be carried:
+///
+/// ```rust,compile_fail
+/// # use std::task::Poll;
+/// # use carrier::{Carry, Completion, IntoCompletion};
+/// struct NotReady;
+///
+/// impl<T, E> IntoCompletion<Poll<Result<T, E>>> for NotReady {
+///     type Value = ();
+///     fn into_completion(self) -> Completion<(), Poll<Result<T, E>>> {
+///         Completion::Abrupt(Poll::Pending)
+///     }
+/// }
+///
+/// fn poll() -> Poll<Result<i32, ()>> {
+///     NotReady.carry::<Poll<Result<i32, ()>>>()?;
+///     Poll::Ready(Ok(42))
+/// }
+/// ```
+pub trait Carry: Sized {
+    /// Converts the value into a carrier that `?` understands.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the abrupt completion holds a successful `R` (for
+    /// instance an `Ok`).  The builtin rules never produce such a
+    /// completion.
+    fn carry<R>(self) -> R::Carried
+        where Self: IntoCompletion<R>,
+              R: CarryTarget<<Self as IntoCompletion<R>>::Value, Self>
+    {
+        R::carried(self.into_completion())
+    }
+}
+
+impl<T> Carry for T {}
+
+/// Describes how a completion is represented for the `?` operator.
+///
+/// This is implemented for the return types supported by `Carry`.  `S`
+/// is the type being carried.
+pub trait CarryTarget<V, S>: Sized {
+    /// The carrier handed to the `?` operator
+    type Carried;
+
+    /// Converts a completion into the carrier for `?`.
+    fn carried(completion: Completion<V, Self>) -> Self::Carried;
+}
+
+impl<V, S, T, E> CarryTarget<V, S> for Result<T, E> {
+    type Carried = Result<V, E>;
+
+    fn carried(completion: Completion<V, Result<T, E>>) -> Result<V, E> {
+        match completion {
+            Completion::Value(value) => Ok(value),
+            Completion::Abrupt(Err(err)) => Err(err),
+            Completion::Abrupt(Ok(_)) => panic!("abrupt completion holds an `Ok` value"),
+        }
+    }
+}
+
+impl<V, S, T> CarryTarget<V, S> for Option<T>
+    where S: IntoCompletion<Option<Infallible>>
 {
-    type Value = T;
+    type Carried = Option<V>;
 
-    fn into_completion(self) -> Completion<T, Result<U, F>> {
-        match self {
-            Ok(value) => Completion::Value(value),
-            Err(err) => Completion::Abrupt(Err(err.into())),
+    fn carried(completion: Completion<V, Option<T>>) -> Option<V> {
+        match completion {
+            Completion::Value(value) => Some(value),
+            Completion::Abrupt(None) => None,
+            Completion::Abrupt(Some(_)) => panic!("abrupt completion holds a `Some` value"),
         }
     }
 }
 
-impl<T, U, E, F> IntoCompletion<Option<Result<U, F>>> for Result<T, E>
-    where E: Into<F>
+impl<V, S, T, E> CarryTarget<V, S> for Poll<Result<T, E>>
+    where S: IntoCompletion<Result<Infallible, E>>
+{
+    type Carried = Result<V, E>;
+
+    fn carried(completion: Completion<V, Poll<Result<T, E>>>) -> Result<V, E> {
+        match completion {
+            Completion::Value(value) => Ok(value),
+            Completion::Abrupt(Poll::Ready(Err(err))) => Err(err),
+            Completion::Abrupt(_) => panic!("abrupt completion holds no error"),
+        }
+    }
+}
+
+impl<V, S, T, E> CarryTarget<V, S> for Poll<Option<Result<T, E>>>
+    where S: IntoCompletion<Result<Infallible, E>>
 {
+    type Carried = Result<V, E>;
+
+    fn carried(completion: Completion<V, Poll<Option<Result<T, E>>>>) -> Result<V, E> {
+        match completion {
+            Completion::Value(value) => Ok(value),
+            Completion::Abrupt(Poll::Ready(Some(Err(err)))) => Err(err),
+            Completion::Abrupt(_) => panic!("abrupt completion holds no error"),
+        }
+    }
+}
+
+/// A trait to take a carrier apart into its value or residual.
+///
+/// The residual is what is left of the carrier on an abrupt completion.
+/// For a `Result<T, E>` this is `Result<Infallible, E>`, for an
+/// `Option<T>` this is `Option<Infallible>`.  The residual is then turned
+/// into the return value of the function with `FromResidual`.
+pub trait Branch {
+    /// The value of the carrier
+    type Value;
+    /// The residual of the carrier on abrupt completion
+    type Residual;
+
+    /// Splits the carrier into a value or a residual.
+    fn branch(self) -> Completion<Self::Value, Self::Residual>;
+}
+
+/// A conversion trait to build a carrier from a residual.
+///
+/// Implementing this for a target carrier makes every `Branch` type with
+/// a matching residual usable with `try!` in functions returning it.
+pub trait FromResidual<Res> {
+    /// Creates the carrier from the given residual.
+    fn from_residual(residual: Res) -> Self;
+}
+
+impl<T, R> IntoCompletion<R> for T
+    where T: Branch, R: FromResidual<T::Residual>
+{
+    type Value = T::Value;
+
+    fn into_completion(self) -> Completion<T::Value, R> {
+        self.branch().map_abrupt(R::from_residual)
+    }
+}
+
+impl<T, E> Branch for Result<T, E> {
     type Value = T;
+    type Residual = Result<Infallible, E>;
 
-    fn into_completion(self) -> Completion<T, Option<Result<U, F>>> {
+    fn branch(self) -> Completion<T, Result<Infallible, E>> {
         match self {
             Ok(value) => Completion::Value(value),
-            Err(err) => Completion::Abrupt(Some(Err(err.into()))),
+            Err(err) => Completion::Abrupt(Err(err)),
         }
     }
 }
 
-impl<U, V> IntoCompletion<Option<V>> for Option<U> {
-    type Value = U;
+impl<T> Branch for Option<T> {
+    type Value = T;
+    type Residual = Option<Infallible>;
 
-    fn into_completion(self) -> Completion<U, Option<V>> {
+    fn branch(self) -> Completion<T, Option<Infallible>> {
         match self {
             Some(value) => Completion::Value(value),
             None => Completion::Abrupt(None),
@@ -203,10 +844,11 @@ impl<U, V> IntoCompletion<Option<V>> for Option<U> {
     }
 }
 
-impl<U, V> IntoCompletion<Option<V>> for *const U {
+impl<U> Branch for *const U {
     type Value = *const U;
+    type Residual = Option<Infallible>;
 
-    fn into_completion(self) -> Completion<*const U, Option<V>> {
+    fn branch(self) -> Completion<*const U, Option<Infallible>> {
         if self.is_null() {
             Completion::Abrupt(None)
         } else {
@@ -215,10 +857,11 @@ impl<U, V> IntoCompletion<Option<V>> for *const U {
     }
 }
 
-impl<U, V> IntoCompletion<Option<V>> for *mut U {
+impl<U> Branch for *mut U {
     type Value = *mut U;
+    type Residual = Option<Infallible>;
 
-    fn into_completion(self) -> Completion<*mut U, Option<V>> {
+    fn branch(self) -> Completion<*mut U, Option<Infallible>> {
         if self.is_null() {
             Completion::Abrupt(None)
         } else {
@@ -226,3 +869,123 @@ impl<U, V> IntoCompletion<Option<V>> for *mut U {
         }
     }
 }
+
+impl<V, F> Branch for Completion<V, F> {
+    type Value = V;
+    type Residual = Completion<Infallible, F>;
+
+    fn branch(self) -> Completion<V, Completion<Infallible, F>> {
+        self.map_abrupt(Completion::Abrupt)
+    }
+}
+
+impl<T, E, F> FromResidual<Result<Infallible, E>> for Result<T, F>
+    where E: Into<F>
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Result<T, F> {
+        match residual {
+            Ok(never) => match never {},
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl<T, E, F> FromResidual<Result<Infallible, E>> for Option<Result<T, F>>
+    where E: Into<F>
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Option<Result<T, F>> {
+        Some(FromResidual::from_residual(residual))
+    }
+}
+
+impl<T, E, F> FromResidual<Result<Infallible, E>> for Poll<Result<T, F>>
+    where E: Into<F>
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Poll<Result<T, F>> {
+        Poll::Ready(FromResidual::from_residual(residual))
+    }
+}
+
+impl<T, E, F> FromResidual<Result<Infallible, E>> for Poll<Option<Result<T, F>>>
+    where E: Into<F>
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Poll<Option<Result<T, F>>> {
+        Poll::Ready(FromResidual::from_residual(residual))
+    }
+}
+
+impl<T, F> FromResidual<Option<Infallible>> for Result<T, F>
+    where NoneError: Into<F>
+{
+    fn from_residual(_residual: Option<Infallible>) -> Result<T, F> {
+        Err(NoneError.into())
+    }
+}
+
+impl<T> FromResidual<Option<Infallible>> for Option<T> {
+    fn from_residual(_residual: Option<Infallible>) -> Option<T> {
+        None
+    }
+}
+
+impl<V, F, G> FromResidual<Completion<Infallible, F>> for Completion<V, G>
+    where F: Into<G>
+{
+    fn from_residual(residual: Completion<Infallible, F>) -> Completion<V, G> {
+        match residual {
+            Completion::Value(never) => match never {},
+            Completion::Abrupt(abrupt) => Completion::Abrupt(abrupt.into()),
+        }
+    }
+}
+
+/// A conversion trait to build a carrier from a success value.
+///
+/// This is the inverse of `IntoCompletion`: it performs the "ok-wrapping"
+/// of a value into the carrier `Self`.  It's used by the `ok!` macro.
+pub trait FromValue<V> {
+    /// Wraps the given value into the carrier.
+    fn from_value(value: V) -> Self;
+}
+
+impl<T, E> FromValue<T> for Result<T, E> {
+    fn from_value(value: T) -> Result<T, E> {
+        Ok(value)
+    }
+}
+
+impl<T> FromValue<T> for Option<T> {
+    fn from_value(value: T) -> Option<T> {
+        Some(value)
+    }
+}
+
+impl<T, E> FromValue<T> for Option<Result<T, E>> {
+    fn from_value(value: T) -> Option<Result<T, E>> {
+        Some(Ok(value))
+    }
+}
+
+impl<T> FromValue<T> for Poll<T> {
+    fn from_value(value: T) -> Poll<T> {
+        Poll::Ready(value)
+    }
+}
+
+impl<T, E> FromValue<T> for Poll<Result<T, E>> {
+    fn from_value(value: T) -> Poll<Result<T, E>> {
+        Poll::Ready(Ok(value))
+    }
+}
+
+impl<T, E> FromValue<T> for Poll<Option<Result<T, E>>> {
+    fn from_value(value: T) -> Poll<Option<Result<T, E>>> {
+        Poll::Ready(Some(Ok(value)))
+    }
+}
+
+impl<V, F> FromValue<V> for Completion<V, F> {
+    fn from_value(value: V) -> Completion<V, F> {
+        Completion::Value(value)
+    }
+}
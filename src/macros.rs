@@ -0,0 +1,708 @@
+//! The macros of this crate.
+
+/// This macro performs error handling through the completion system.
+///
+/// In the future this will be implemented with the `?` operator instead.
+///
+/// Besides the plain form the macro accepts extended forms to adjust
+/// the error at the call site before it's propagated:
+///
+/// * `try!(expr => f)` maps the error of an abrupt completion with `f`
+///   first.  For `Option`s and raw pointers the error is a `NoneError`.
+/// * `try!(expr else f)` replaces the error of an abrupt completion with
+///   the one produced by calling `f`.  This is most useful for `Option`s
+///   and raw pointers.
+///
+/// * `try!(expr; "format {}", args)` wraps the error of an abrupt
+///   completion in a `Context` with the formatted message.  If the error
+///   is already a `Context` the message is added to its trail.
+///
+/// * `try!(?cond expr)` offers the error of an abrupt completion to the
+///   condition handlers first if the `conditions` feature is enabled.
+///   See the `condition` module for details.
+///
+/// Any of the forms can be prefixed with a label as in
+/// `try!(#"db-read" expr)`.  The label is recorded in the call site and
+/// makes the invocation selectable by the `fault-injection` feature.
+///
+/// In all cases the resulting error still goes through the regular
+/// `IntoCompletion` rules, so it's converted with `Into` as usual:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # use std::collections::HashMap;
+/// #[derive(Debug)]
+/// enum MyError {
+///     BadNumber(std::num::ParseIntError),
+///     MissingKey(String),
+/// }
+///
+/// fn lookup(map: &HashMap<String, String>, key: &str) -> Result<i32, MyError> {
+///     let raw = try!(map.get(key) else || MyError::MissingKey(key.into()));
+///     Ok(try!(raw.parse() => MyError::BadNumber))
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! try {
+    ($($tokens:tt)+) => {
+        $crate::__carrier_try!([return] [] $($tokens)+)
+    };
+}
+
+/// Parses the forms of `try!`.
+///
+/// The first argument is the expression used to leave the scope on an
+/// abrupt completion: `return` for `try!` and a labeled `break` within
+/// `try_block!`.  The second argument holds the label of the invocation
+/// if one was given.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_try {
+    ([$($exit:tt)+] [] # $label:literal $($rest:tt)+) => {
+        $crate::__carrier_try!([$($exit)+] [$label] $($rest)+)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] ? cond $expr:expr) => {
+        $crate::__carrier_complete!([$($exit)+] [$($label)*] [$expr] []
+            $crate::__private::signal_condition($expr))
+    };
+    ([$($exit:tt)+] [$($label:tt)*] @source [$($source:tt)+] $expr:expr) => {
+        $crate::__carrier_complete!([$($exit)+] [$($label)*] [$($source)+] [] $expr)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] @else [$($expr:tt)*] else { $($body:tt)* } $($rest:tt)*) => {
+        $crate::__carrier_try!([$($exit)+] [$($label)*] @else [$($expr)* else { $($body)* }] $($rest)*)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] @else [$($expr:tt)*] else if $($rest:tt)*) => {
+        $crate::__carrier_try!([$($exit)+] [$($label)*] @else [$($expr)* else if] $($rest)*)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] @else [$($expr:tt)+] else $op:expr) => {
+        $crate::__carrier_complete!([$($exit)+] [$($label)*] [$($expr)+]
+            [$crate::__private::else_abrupt, $op] $($expr)+)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] @else [$($expr:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__carrier_try!([$($exit)+] [$($label)*] @else [$($expr)* $token] $($rest)*)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] $expr:expr => $op:expr) => {
+        $crate::__carrier_complete!([$($exit)+] [$($label)*] [$expr]
+            [$crate::__private::map_abrupt, $op] $expr)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] $expr:expr ; $($fmt:tt)+) => {
+        $crate::__carrier_complete!([$($exit)+] [$($label)*] [$expr]
+            [$crate::__private::map_abrupt, |err| {
+                $crate::Context::new(err, format!($($fmt)+))
+            }] $expr)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] $expr:expr) => {
+        $crate::__carrier_complete!([$($exit)+] [$($label)*] [$expr] [] $expr)
+    };
+    ([$($exit:tt)+] [$($label:tt)*] $($tokens:tt)+) => {
+        $crate::__carrier_try!([$($exit)+] [$($label)*] @else [] $($tokens)+)
+    };
+}
+
+/// Converts the expression into a completion and leaves on abrupt.
+///
+/// The fourth argument optionally holds a function and its argument
+/// that adjust the error of the expression before the conversion.  The
+/// label and the expression as written in the `try!` invocation are only
+/// used by the instrumented version.
+#[cfg(not(any(feature = "trace", feature = "hooks", feature = "stats",
+              feature = "fault-injection")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_complete {
+    ([$($exit:tt)+] [$($label:tt)*] [$($source:tt)+] [$($adapt:tt)*] $expr:expr) => {
+        match $crate::IntoCompletion::into_completion($crate::__carrier_adapt!([$($adapt)*] $expr)) {
+            $crate::Completion::Value(x) => x,
+            $crate::Completion::Abrupt(x) => { $($exit)+ x; }
+        }
+    }
+}
+
+/// Converts the expression into a completion and leaves on abrupt.
+///
+/// This version instruments the call site.  The expression stays within
+/// the scrutinee so that its temporaries live as long as without the
+/// instrumentation.  The abrupt value type is pinned to the type of the
+/// scope's return value so that it can be inspected together with the
+/// type of the expression by the autoref dispatch of
+/// `__private::TraceProbe`.  The residual of the expression is reported
+/// to the abrupt hook by the autoref dispatch of `__private::Probe`.
+#[cfg(any(feature = "trace", feature = "hooks", feature = "stats",
+          feature = "fault-injection"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_complete {
+    ([$($exit:tt)+] [$($label:tt)*] [$($source:tt)+] [$($adapt:tt)*] $expr:expr) => {{
+        static __CARRIER_SITE: $crate::CallSite = $crate::CallSite::__new(
+            file!(), line!(), column!(), module_path!(),
+            $crate::__carrier_expression!($($source)+),
+            $crate::__private::label(&[$($label)*]));
+        static __CARRIER_COUNTER: $crate::__private::SiteCounter =
+            $crate::__private::SiteCounter::new(&__CARRIER_SITE);
+        let source_type = $crate::__private::PhantomData;
+        match {
+            #[allow(unused_imports)]
+            use $crate::__private::{ReportOpaque, ReportResidual};
+            (&mut $crate::__private::Probe(&mut Some($crate::__carrier_adapt!([$($adapt)*]
+                $crate::__carrier_inject!([$($label)*] __CARRIER_SITE $expr)))))
+                .complete(&__CARRIER_SITE, &source_type)
+        } {
+            $crate::Completion::Value(x) => {
+                __CARRIER_COUNTER.count_value();
+                x
+            }
+            $crate::Completion::Abrupt(x) => {
+                __CARRIER_COUNTER.count_abrupt();
+                let mut x = x;
+                if false {
+                    $($exit)+ $crate::__private::same_type(&x);
+                }
+                {
+                    #[allow(unused_imports)]
+                    use $crate::__private::{RecordTrace, SkipTrace};
+                    (&mut $crate::__private::TraceProbe(&mut x, source_type))
+                        .record_trace(&__CARRIER_SITE);
+                }
+                $($exit)+ x;
+            }
+        }
+    }}
+}
+
+/// Replaces the value of a labeled `try!` expression with a fault.
+///
+/// Only labeled invocations can be selected, plain ones are expanded
+/// without the fault injection.
+#[cfg(feature = "fault-injection")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_inject {
+    ([] $site:ident $expr:expr) => { $expr };
+    ([$($label:tt)+] $site:ident $expr:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{InjectFault, RejectFault};
+        (&mut $crate::__private::Probe(&mut Some($expr))).inject_fault(&$site)
+    }};
+}
+
+/// Replaces the value of a labeled `try!` expression with a fault.
+#[cfg(not(feature = "fault-injection"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_inject {
+    ([$($label:tt)*] $site:ident $expr:expr) => { $expr };
+}
+
+/// Adjusts the error of a `try!` expression with the given function.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_adapt {
+    ([] $expr:expr) => { $expr };
+    ([$adapt:path, $op:expr] $expr:expr) => { $adapt($expr, $op) };
+}
+
+/// The source text of the expression recorded in the call site.
+#[cfg(feature = "trace-expr")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_expression {
+    ($($source:tt)+) => { Some(stringify!($($source)+)) }
+}
+
+/// The source text of the expression recorded in the call site.
+#[cfg(not(feature = "trace-expr"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_expression {
+    ($($source:tt)+) => { None }
+}
+
+/// This macro wraps a value into the carrier returned by the function.
+///
+/// It is the counterpart to `try!`: where `try!` takes a carrier apart,
+/// `ok!` puts a success value back into one through the `FromValue`
+/// trait.  Which carrier is produced is inferred from the context, so the
+/// same `ok!(value)` becomes `Ok(value)`, `Some(value)` or
+/// `Some(Ok(value))` depending on the return type.  Invoked without
+/// arguments it wraps `()`.
+#[macro_export]
+macro_rules! ok {
+    () => {
+        $crate::FromValue::from_value(())
+    };
+    ($expr:expr) => {
+        $crate::FromValue::from_value($expr)
+    }
+}
+
+/// This macro unwraps a value or continues with the next loop iteration.
+///
+/// The source is converted through `IntoCompletion` like with `try!`.
+/// Carriers implementing `Branch` are converted into their residual and
+/// carriers that only implement `IntoCompletion` are converted into a
+/// `Result`.  On an abrupt completion the optional handler is invoked
+/// with the error of the residual (`NoneError` for `None`) before the
+/// loop continues.  If the error of a custom carrier is generic it is
+/// inferred from the handler, so such carriers need a handler with a
+/// typed parameter.  A loop label can be given as first argument:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # fn main() {
+/// let mut sum = 0;
+/// let mut failed = vec![];
+/// for item in &["1", "x", "2"] {
+///     sum += try_continue!(item.parse::<i32>(), |err| failed.push(err));
+/// }
+/// assert_eq!(sum, 3);
+/// assert_eq!(failed.len(), 1);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_continue {
+    ($label:lifetime, $expr:expr) => {
+        $crate::__carrier_loop!([continue $label] $expr)
+    };
+    ($label:lifetime, $expr:expr, $handler:expr) => {
+        $crate::__carrier_loop!([continue $label] $expr, $handler)
+    };
+    ($expr:expr) => {
+        $crate::__carrier_loop!([continue] $expr)
+    };
+    ($expr:expr, $handler:expr) => {
+        $crate::__carrier_loop!([continue] $expr, $handler)
+    };
+}
+
+/// This macro unwraps a value or breaks out of the loop.
+///
+/// This works like `try_continue!` but leaves the loop on an abrupt
+/// completion:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # fn main() {
+/// let mut values = vec![];
+/// for item in &["1", "2", "x", "3"] {
+///     values.push(try_break!(item.parse::<i32>()));
+/// }
+/// assert_eq!(values, vec![1, 2]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_break {
+    ($label:lifetime, $expr:expr) => {
+        $crate::__carrier_loop!([break $label] $expr)
+    };
+    ($label:lifetime, $expr:expr, $handler:expr) => {
+        $crate::__carrier_loop!([break $label] $expr, $handler)
+    };
+    ($expr:expr) => {
+        $crate::__carrier_loop!([break] $expr)
+    };
+    ($expr:expr, $handler:expr) => {
+        $crate::__carrier_loop!([break] $expr, $handler)
+    };
+}
+
+/// Expands `try_continue!` and `try_break!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_loop {
+    ([$($exit:tt)+] $expr:expr) => {
+        $crate::__carrier_loop!([$($exit)+] $expr, |_| ())
+    };
+    ([$($exit:tt)+] $expr:expr, $handler:expr) => {
+        match {
+            #[allow(unused_imports)]
+            use $crate::__private::{LoopBranch, LoopConvert};
+            (&mut $crate::__private::Probe(&mut Some($expr))).complete()
+        } {
+            $crate::Completion::Value(x) => x,
+            $crate::Completion::Abrupt(err) => {
+                $crate::__private::handle_error(err, $handler);
+                $($exit)+;
+            }
+        }
+    };
+}
+
+/// This macro evaluates carriers in order and returns the first value.
+///
+/// The carriers are evaluated lazily and may be of different types as
+/// long as their values are of the same type.  If none of them holds a
+/// value, the residual of the last one is propagated from the function
+/// like with `try!`:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # use std::num::ParseIntError;
+/// fn from_cache() -> Option<i32> { None }
+/// fn from_disk() -> Result<i32, ParseIntError> { "x".parse() }
+///
+/// fn load() -> Result<i32, ParseIntError> {
+///     Ok(first_ok!(from_cache(), from_disk(), "42".parse::<i32>()))
+/// }
+/// # fn main() {
+/// assert_eq!(load(), Ok(42));
+/// # }
+/// ```
+///
+/// With `first_ok!(all: a, b, c)` the errors of all carriers are combined
+/// instead and propagated as the residual of a `Validated`, so that a
+/// function returning a `Result<T, Vec<E>>` receives all of them.  In
+/// that form the carriers must have the same error type.
+///
+/// Within `try_block!`, `outcome!` or `chain!` the residual is propagated
+/// by the `try!` of the block, so it leaves the block instead of the
+/// function.
+#[macro_export]
+macro_rules! first_ok {
+    ($($tokens:tt)+) => {
+        $crate::__carrier_first_ok!([$crate::try] $($tokens)+)
+    };
+}
+
+/// Expands `first_ok!`.
+///
+/// The first argument is the path of the `try!` macro that propagates
+/// the residual.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_first_ok {
+    ([$($try:tt)+] all: $($expr:expr),+ $(,)*) => {{
+        let mut errors = None;
+        $crate::__carrier_first_ok!([$($try)+] @all errors [$($expr),+])
+    }};
+    ([$($try:tt)+] @all $errors:ident [$last:expr]) => {
+        match $crate::Branch::branch($last) {
+            $crate::Completion::Value(x) => x,
+            $crate::Completion::Abrupt(residual) => {
+                $crate::__private::push_error(&mut $errors, residual);
+                match $($try)+!($crate::__private::all_failed($errors)) {}
+            }
+        }
+    };
+    ([$($try:tt)+] @all $errors:ident [$first:expr, $($rest:expr),+]) => {
+        match $crate::Branch::branch($first) {
+            $crate::Completion::Value(x) => x,
+            $crate::Completion::Abrupt(residual) => {
+                $crate::__private::push_error(&mut $errors, residual);
+                $crate::__carrier_first_ok!([$($try)+] @all $errors [$($rest),+])
+            }
+        }
+    };
+    ([$($try:tt)+] @any [$last:expr]) => {
+        $($try)+!($last)
+    };
+    ([$($try:tt)+] @any [$first:expr, $($rest:expr),+]) => {
+        match $crate::Branch::branch($first) {
+            $crate::Completion::Value(x) => x,
+            $crate::Completion::Abrupt(_) => $crate::__carrier_first_ok!([$($try)+] @any [$($rest),+]),
+        }
+    };
+    ([$($try:tt)+] $($expr:expr),+ $(,)*) => {
+        $crate::__carrier_first_ok!([$($try)+] @any [$($expr),+])
+    };
+}
+
+/// This macro sequences carriers in expression position.
+///
+/// `chain!` is a do-notation for carriers.  Every `name <- expr;` binds
+/// the value of a carrier which is converted through the `IntoCompletion`
+/// rules.  On an abrupt completion the whole `chain!` evaluates to the
+/// converted abrupt value instead of returning from the function.  The
+/// chain ends with `pure expr` which wraps the value with `FromValue`:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # fn main() {
+/// let sum: Option<i32> = chain! {
+///     x <- "20".parse::<i32>().ok();
+///     y <- "22".parse::<i32>().ok();
+///     pure x + y
+/// };
+/// assert_eq!(sum, Some(42));
+/// # }
+/// ```
+///
+/// Besides binds the chain can contain `let` statements, optionally with
+/// a type annotation, and carriers that are evaluated for their
+/// completion only (`expr;`).  A chain can
+/// also end in a carrier instead of `pure`, which is then converted like
+/// a bind.  A `try!` within the chain leaves the chain like a bind.
+#[macro_export]
+macro_rules! chain {
+    ($($body:tt)+) => {
+        $crate::__carrier_chain!(($) $($body)+)
+    }
+}
+
+/// Expands the statements of `chain!`.
+///
+/// The chain is a labeled block in which `try!` is shadowed like in
+/// `__carrier_try_block!`.  For the statements the first argument is the
+/// expression used to leave the chain.  The pattern and type of a `let`
+/// statement are collected token by token up to the `=`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_chain {
+    (($d:tt) $($body:tt)+) => {
+        '__carrier_chain: {
+            #[allow(unused_macros)]
+            macro_rules! try {
+                ($d($d tokens:tt)+) => {
+                    $crate::__carrier_try!([break '__carrier_chain] [] $d($d tokens)+)
+                };
+            }
+            #[allow(unused_macros)]
+            macro_rules! first_ok {
+                ($d($d tokens:tt)+) => {
+                    $crate::__carrier_first_ok!([try] $d($d tokens)+)
+                };
+            }
+            $crate::__carrier_chain!([break '__carrier_chain] $($body)+)
+        }
+    };
+    ([$($exit:tt)+] pure $value:expr) => {
+        $crate::FromValue::from_value($value)
+    };
+    ([$($exit:tt)+] let $($rest:tt)+) => {
+        $crate::__carrier_chain!([$($exit)+] @let [] $($rest)+)
+    };
+    ([$($exit:tt)+] @let [$($decl:tt)+] = $value:expr; $($rest:tt)+) => {{
+        let $($decl)+ = $value;
+        $crate::__carrier_chain!([$($exit)+] $($rest)+)
+    }};
+    ([$($exit:tt)+] @let [$($decl:tt)*] $token:tt $($rest:tt)+) => {
+        $crate::__carrier_chain!([$($exit)+] @let [$($decl)* $token] $($rest)+)
+    };
+    ([$($exit:tt)+] $name:ident <- $expr:expr; $($rest:tt)+) => {{
+        let $name = $crate::__carrier_try!([$($exit)+] [] $expr);
+        $crate::__carrier_chain!([$($exit)+] $($rest)+)
+    }};
+    ([$($exit:tt)+] $expr:expr; $($rest:tt)+) => {{
+        let _ = $crate::__carrier_try!([$($exit)+] [] $expr);
+        $crate::__carrier_chain!([$($exit)+] $($rest)+)
+    }};
+    ([$($exit:tt)+] $expr:expr) => {
+        $crate::FromValue::from_value($crate::__carrier_try!([$($exit)+] [] $expr))
+    };
+}
+
+/// This macro evaluates the body of a function returning an `Outcome`.
+///
+/// Within the body `try!(expr)` on an `Outcome` moves its warnings into
+/// an accumulator and unwraps the value, also if the invocation has a
+/// label.  On a fatal error the body is left and the accumulated warnings
+/// are kept.  The value of the body is wrapped into an `Outcome` with all
+/// accumulated warnings.  See the `outcome` module for an example.
+#[macro_export]
+macro_rules! outcome {
+    ($($body:tt)*) => {
+        $crate::__carrier_outcome!(($) $($body)*)
+    }
+}
+
+/// Expands `outcome!`.
+///
+/// This works like `__carrier_try_block!` but the shadowing `try!` also
+/// merges the warnings of the plain and labeled forms into the
+/// accumulator.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_outcome {
+    (($d:tt) $($body:tt)*) => {{
+        let mut warnings = Vec::new();
+        $crate::outcome::Outcome::prepend_warnings('__carrier_outcome: {
+            #[allow(unused_macros)]
+            macro_rules! try {
+                (# $d label:literal $d expr:expr) => {
+                    $crate::__carrier_try!([break '__carrier_outcome] [$d label] @source [$d expr] {
+                        #[allow(unused_imports)]
+                        use $crate::__private::{KeepWarnings, MergeWarnings};
+                        (&mut $crate::__private::Probe(&mut Some($d expr)))
+                            .merge_warnings(&mut warnings)
+                    })
+                };
+                ($d expr:expr) => {
+                    $crate::__carrier_try!([break '__carrier_outcome] [] @source [$d expr] {
+                        #[allow(unused_imports)]
+                        use $crate::__private::{KeepWarnings, MergeWarnings};
+                        (&mut $crate::__private::Probe(&mut Some($d expr)))
+                            .merge_warnings(&mut warnings)
+                    })
+                };
+                ($d($d tokens:tt)+) => {
+                    $crate::__carrier_try!([break '__carrier_outcome] [] $d($d tokens)+)
+                };
+            }
+            #[allow(unused_macros)]
+            macro_rules! first_ok {
+                ($d($d tokens:tt)+) => {
+                    $crate::__carrier_first_ok!([try] $d($d tokens)+)
+                };
+            }
+            $crate::FromValue::from_value({ $($body)* })
+        }, warnings)
+    }}
+}
+
+/// This macro evaluates several sources and accumulates their errors.
+///
+/// Every source is converted through the `IntoCompletion` rules.  The
+/// result is a `Validated` holding a tuple of all values or the errors
+/// of all sources that completed abruptly:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # use carrier::Validated;
+/// # fn main() {
+/// let rv: Validated<(i32, i32), std::num::ParseIntError> =
+///     validate!("1".parse::<i32>(), "x".parse::<i32>());
+/// assert_eq!(rv.errors().map(|x| x.len()), Some(1));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! validate {
+    ($($source:expr),+ $(,)*) => {
+        $crate::validated::Zip::zip(($($crate::Validated::from_source($source),)+))
+    }
+}
+
+/// This macro evaluates a block in which `try!` only exits the block.
+///
+/// Every `try!` invoked within the block short-circuits out of the block
+/// instead of the enclosing function.  The block evaluates to a carrier
+/// which is inferred from the context: on success the value of the
+/// block is wrapped with `FromValue`, on an abrupt completion the
+/// converted abrupt value of the failing `try!` is produced.
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # fn main() {
+/// let sum: Result<i32, std::num::ParseIntError> = try_block! {
+///     try!("20".parse::<i32>()) + try!("22".parse::<i32>())
+/// };
+/// assert_eq!(sum, Ok(42));
+/// # }
+/// ```
+///
+/// The block is implemented as a labeled block in which `try!` is
+/// shadowed by a version that breaks out of the block.  A `return`
+/// written directly within the block still returns from the enclosing
+/// function.
+#[macro_export]
+macro_rules! try_block {
+    ($($body:tt)*) => {
+        $crate::__carrier_try_block!(($) $($body)*)
+    }
+}
+
+/// Expands `try_block!`.
+///
+/// The dollar sign is passed in as `$d` so that the shadowing `try!`
+/// macro can be defined within the expansion.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __carrier_try_block {
+    (($d:tt) $($body:tt)*) => {
+        '__carrier_try_block: {
+            #[allow(unused_macros)]
+            macro_rules! try {
+                ($d($d tokens:tt)+) => {
+                    $crate::__carrier_try!([break '__carrier_try_block] [] $d($d tokens)+)
+                };
+            }
+            #[allow(unused_macros)]
+            macro_rules! first_ok {
+                ($d($d tokens:tt)+) => {
+                    $crate::__carrier_first_ok!([try] $d($d tokens)+)
+                };
+            }
+            $crate::FromValue::from_value({ $($body)* })
+        }
+    }
+}
+
+/// This macro runs a closure if a block is left early.
+///
+/// The block is evaluated and its value returned.  If it's left before
+/// it completes, through a `try!`, a `return`, a `break` or a panic, the
+/// closure runs while the block is unwound.  This is useful to clean up
+/// after a function that fails midway:
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # use std::cell::RefCell;
+/// fn append(log: &RefCell<Vec<i32>>, raw: &str) -> Result<i32, std::num::ParseIntError> {
+///     log.borrow_mut().push(0);
+///     on_abrupt!(|| { log.borrow_mut().pop(); }, {
+///         let value = try!(raw.parse::<i32>());
+///         *log.borrow_mut().last_mut().unwrap() = value;
+///         Ok(value)
+///     })
+/// }
+///
+/// # fn main() {
+/// let log = RefCell::new(vec![]);
+/// assert!(append(&log, "x").is_err());
+/// assert_eq!(append(&log, "42"), Ok(42));
+/// assert_eq!(*log.borrow(), vec![42]);
+/// # }
+/// ```
+///
+/// The block is guarded by a `Transaction` which is committed when the
+/// block completes.
+#[macro_export]
+macro_rules! on_abrupt {
+    ($cleanup:expr, $body:block) => {{
+        let transaction = $crate::Transaction::new($cleanup);
+        let value = $body;
+        transaction.commit();
+        value
+    }}
+}
+
+/// An alias for `on_abrupt!`.
+///
+/// `defer_err!(cleanup, { ... })` runs the cleanup closure only if the
+/// block is left early, exactly like `on_abrupt!`.
+#[macro_export]
+macro_rules! defer_err {
+    ($cleanup:expr, $body:block) => {
+        $crate::on_abrupt!($cleanup, $body)
+    }
+}
+
+/// This macro runs a closure if a block completes normally.
+///
+/// It's the counterpart to `on_abrupt!`: the closure runs after the
+/// block produced its value, but not if the block is left early through
+/// a `try!`, a `return`, a `break` or a panic.
+///
+/// ```rust
+/// # #[macro_use] extern crate carrier;
+/// # use std::cell::Cell;
+/// fn parse(parsed: &Cell<usize>, raw: &str) -> Result<i32, std::num::ParseIntError> {
+///     on_value!(|| parsed.set(parsed.get() + 1), {
+///         Ok(try!(raw.parse::<i32>()))
+///     })
+/// }
+///
+/// # fn main() {
+/// let parsed = Cell::new(0);
+/// assert!(parse(&parsed, "x").is_err());
+/// assert_eq!(parse(&parsed, "42"), Ok(42));
+/// assert_eq!(parsed.get(), 1);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! on_value {
+    ($finish:expr, $body:block) => {{
+        let value = $body;
+        ($finish)();
+        value
+    }}
+}
@@ -0,0 +1,95 @@
+//! Integration with the unstable `std::ops::Try` trait.
+//!
+//! With the `nightly` feature enabled `Completion` implements `Try` and
+//! can be used with the `?` operator.  This also turns `IntoCompletion`
+//! into an adapter for `?`: any type that works with `try!` can be
+//! propagated with `?` by converting it with `into_completion()` first.
+//!
+//! ```rust
+//! # use carrier::Completion;
+//! # #[derive(Debug, PartialEq)]
+//! # struct Error(i32);
+//! # struct Response { status: i32 }
+//! # impl<E: From<Error>, T> IntoCompletion<Result<T, E>> for Response {
+//! #     type Value = Response;
+//! #     fn into_completion(self) -> Completion<Response, Result<T, E>> {
+//! #         if self.status == 200 {
+//! #             Completion::Value(self)
+//! #         } else {
+//! #             Completion::Abrupt(Err(Error(self.status).into()))
+//! #         }
+//! #     }
+//! # }
+//! use carrier::IntoCompletion;
+//!
+//! fn http_test(status: i32) -> Result<(), Error> {
+//!     let _resp = Response { status }.into_completion()?;
+//!     Ok(())
+//! }
+//! # assert_eq!(http_test(404), Err(Error(404)));
+//! # assert_eq!(http_test(200), Ok(()));
+//! ```
+//!
+//! The completion produced by `into_completion` already holds the return
+//! value of the function in its abrupt case, so `?` performs exactly the
+//! same conversions as `try!` does.
+use std::convert::Infallible;
+use std::ops::{ControlFlow, FromResidual, Residual, Try};
+use std::task::Poll;
+
+use Completion;
+
+impl<V, F> Try for Completion<V, F> {
+    type Output = V;
+    type Residual = Completion<Infallible, F>;
+
+    fn from_output(output: V) -> Completion<V, F> {
+        Completion::Value(output)
+    }
+
+    fn branch(self) -> ControlFlow<Completion<Infallible, F>, V> {
+        match self {
+            Completion::Value(value) => ControlFlow::Continue(value),
+            Completion::Abrupt(abrupt) => ControlFlow::Break(Completion::Abrupt(abrupt)),
+        }
+    }
+}
+
+impl<V, F> Residual<V> for Completion<Infallible, F> {
+    type TryType = Completion<V, F>;
+}
+
+impl<V, F, G> FromResidual<Completion<Infallible, F>> for Completion<V, G>
+    where F: Into<G>
+{
+    fn from_residual(residual: Completion<Infallible, F>) -> Completion<V, G> {
+        ::FromResidual::from_residual(residual)
+    }
+}
+
+impl<T, E> FromResidual<Completion<Infallible, Result<T, E>>> for Result<T, E> {
+    fn from_residual(residual: Completion<Infallible, Result<T, E>>) -> Result<T, E> {
+        match residual {
+            Completion::Value(never) => match never {},
+            Completion::Abrupt(abrupt) => abrupt,
+        }
+    }
+}
+
+impl<T> FromResidual<Completion<Infallible, Option<T>>> for Option<T> {
+    fn from_residual(residual: Completion<Infallible, Option<T>>) -> Option<T> {
+        match residual {
+            Completion::Value(never) => match never {},
+            Completion::Abrupt(abrupt) => abrupt,
+        }
+    }
+}
+
+impl<T> FromResidual<Completion<Infallible, Poll<T>>> for Poll<T> {
+    fn from_residual(residual: Completion<Infallible, Poll<T>>) -> Poll<T> {
+        match residual {
+            Completion::Value(never) => match never {},
+            Completion::Abrupt(abrupt) => abrupt,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Values with warnings.
+//!
+//! An `Outcome` is a carrier for operations that can succeed with
+//! warnings, such as loading a config file with deprecated keys.  It
+//! holds either a value or a fatal error, and in both cases the warnings
+//! that were produced along the way.
+//!
+//! The body of a function returning an `Outcome` is best wrapped in the
+//! `outcome!` macro.  Within it `try!` on another `Outcome` unwraps the
+//! value and merges the warnings into a local accumulator.  Fatal errors
+//! short-circuit as usual and keep the warnings accumulated so far:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! use carrier::Outcome;
+//!
+//! fn read_key(key: &str) -> Outcome<i32, String, std::num::ParseIntError> {
+//!     Outcome::new(42).with_warning(format!("{} is deprecated", key))
+//! }
+//!
+//! fn load() -> Outcome<i32, String, std::num::ParseIntError> {
+//!     outcome! {
+//!         let a = try!(read_key("a"));
+//!         let b = try!(read_key("b"));
+//!         let c = try!("1".parse::<i32>());
+//!         a + b + c
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let rv = load();
+//! assert_eq!(rv.warnings().len(), 2);
+//! assert_eq!(rv.into_result(), Ok(85));
+//! # }
+//! ```
+//!
+//! Plain results and options can be mixed in as their residuals convert
+//! into the fatal error.
+//!
+//! An `Outcome` is deliberately not a carrier by itself so that its
+//! warnings cannot get lost: `try!` only accepts it within `outcome!`,
+//! and only in the plain form `try!(expr)`, optionally with a label.
+//! Elsewhere the warnings have to be dealt with explicitly, for instance
+//! with `into_parts` or by discarding them with `into_result`.
+use std::convert::Infallible;
+
+use {FromResidual, FromValue, NoneError, ResidualError};
+
+/// A value or fatal error together with warnings.
+///
+/// The warnings are only merged by `try!` within `outcome!`.  An outcome
+/// can't be passed to `try!` or the other macros of this crate anywhere
+/// else, as that would drop its warnings:
+///
+/// ```rust,compile_fail
+/// # #[macro_use] extern crate carrier;
+/// # use carrier::Outcome;
+/// fn load() -> Result<i32, String> {
+///     Ok(try!(Outcome::<i32, String, String>::new(1).with_warning("lost".into())))
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Outcome<T, W, E> {
+    result: Result<T, E>,
+    warnings: Vec<W>,
+}
+
+impl<T, W, E> Outcome<T, W, E> {
+    /// Creates a successful outcome without warnings.
+    pub fn new(value: T) -> Outcome<T, W, E> {
+        Outcome::from_parts(Ok(value), Vec::new())
+    }
+
+    /// Creates a fatal outcome without warnings.
+    pub fn fatal(error: E) -> Outcome<T, W, E> {
+        Outcome::from_parts(Err(error), Vec::new())
+    }
+
+    /// Creates an outcome from a result and warnings.
+    pub fn from_parts(result: Result<T, E>, warnings: Vec<W>) -> Outcome<T, W, E> {
+        Outcome { result, warnings }
+    }
+
+    /// Adds a warning.
+    pub fn with_warning(mut self, warning: W) -> Outcome<T, W, E> {
+        self.warnings.push(warning);
+        self
+    }
+
+    /// Adds a warning in place.
+    pub fn push_warning(&mut self, warning: W) {
+        self.warnings.push(warning);
+    }
+
+    /// Returns `true` if the outcome holds a fatal error.
+    pub fn is_fatal(&self) -> bool {
+        self.result.is_err()
+    }
+
+    /// Returns a reference to the result.
+    pub fn result(&self) -> Result<&T, &E> {
+        self.result.as_ref()
+    }
+
+    /// The warnings of the outcome.
+    pub fn warnings(&self) -> &[W] {
+        &self.warnings
+    }
+
+    /// Maps the value with a function, keeping the warnings.
+    pub fn map<U, O: FnOnce(T) -> U>(self, op: O) -> Outcome<U, W, E> {
+        Outcome::from_parts(self.result.map(op), self.warnings)
+    }
+
+    /// Splits the outcome into the result and the warnings.
+    pub fn into_parts(self) -> (Result<T, E>, Vec<W>) {
+        (self.result, self.warnings)
+    }
+
+    /// Converts the outcome into a result, discarding the warnings.
+    pub fn into_result(self) -> Result<T, E> {
+        self.result
+    }
+
+    /// Moves the warnings into an accumulator, returning the result.
+    ///
+    /// This is used by `try!` within the `outcome!` macro.
+    pub fn drain_warnings<V: From<W>>(self, warnings: &mut Vec<V>) -> Result<T, E> {
+        warnings.extend(self.warnings.into_iter().map(V::from));
+        self.result
+    }
+
+    /// Inserts accumulated warnings in front of the warnings.
+    ///
+    /// This is used by the `outcome!` macro.
+    pub fn prepend_warnings(mut self, mut warnings: Vec<W>) -> Outcome<T, W, E> {
+        warnings.append(&mut self.warnings);
+        self.warnings = warnings;
+        self
+    }
+}
+
+impl<T, W, E> From<Outcome<T, W, E>> for Result<T, E> {
+    fn from(outcome: Outcome<T, W, E>) -> Result<T, E> {
+        outcome.into_result()
+    }
+}
+
+impl<T, W, E, F> FromResidual<Result<Infallible, F>> for Outcome<T, W, E>
+    where F: Into<E>
+{
+    fn from_residual(residual: Result<Infallible, F>) -> Outcome<T, W, E> {
+        Outcome::fatal(residual.into_error().into())
+    }
+}
+
+impl<T, W, E> FromResidual<Option<Infallible>> for Outcome<T, W, E>
+    where NoneError: Into<E>
+{
+    fn from_residual(_residual: Option<Infallible>) -> Outcome<T, W, E> {
+        Outcome::fatal(NoneError.into())
+    }
+}
+
+impl<T, W, E> FromValue<T> for Outcome<T, W, E> {
+    fn from_value(value: T) -> Outcome<T, W, E> {
+        Outcome::new(value)
+    }
+}
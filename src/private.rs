@@ -0,0 +1,413 @@
+//! Support code for the macros of this crate.  Not public API.
+#[cfg(any(feature = "hooks", feature = "fault-injection"))]
+use std::any;
+use std::convert::Infallible;
+pub use std::marker::PhantomData;
+#[cfg(feature = "hooks")]
+use std::fmt;
+#[cfg(feature = "stats")]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+#[cfg(feature = "conditions")]
+use condition::{self, Condition};
+#[cfg(feature = "fault-injection")]
+use fault;
+#[cfg(feature = "fault-injection")]
+use NoneError;
+#[cfg(feature = "hooks")]
+use hook;
+#[cfg(feature = "stats")]
+use stats;
+#[cfg(feature = "trace")]
+use trace::{self, Traced};
+use outcome::Outcome;
+use validated::{Errors, Validated};
+use {Branch, CallSite, Completion, FromResidual, IntoCompletion, ResidualError};
+
+type Error<S> = <<S as Branch>::Residual as ResidualError>::Error;
+
+/// Converts a carrier into a result with the error mapped by `op`.
+pub fn map_abrupt<S, O, E>(source: S, op: O) -> Result<S::Value, E>
+    where S: Branch, S::Residual: ResidualError, O: FnOnce(Error<S>) -> E
+{
+    source.branch().map_abrupt(|residual| op(residual.into_error())).into_result()
+}
+
+/// Converts a carrier into a result with the error produced by `op`.
+pub fn else_abrupt<S, O, E>(source: S, op: O) -> Result<S::Value, E>
+    where S: Branch, O: FnOnce() -> E
+{
+    source.branch().map_abrupt(|_| op()).into_result()
+}
+
+/// Converts the expression of `try_continue!` and `try_break!` into a
+/// completion that holds the error of its residual.
+pub trait LoopBranch {
+    type Value;
+    type Error;
+    fn complete(&mut self) -> Completion<Self::Value, Self::Error>;
+}
+
+/// Fallback for carriers that only implement `IntoCompletion` into a
+/// `Result`.
+pub trait LoopConvert {
+    type Source;
+    fn complete<E>(&mut self)
+        -> Completion<<Self::Source as IntoCompletion<Result<Infallible, E>>>::Value, E>
+        where Self::Source: IntoCompletion<Result<Infallible, E>>;
+}
+
+impl<'a, S> LoopBranch for Probe<'a, Option<S>>
+    where S: IntoCompletion<<S as Branch>::Residual> + Branch, S::Residual: ResidualError
+{
+    type Value = <S as IntoCompletion<S::Residual>>::Value;
+    type Error = Error<S>;
+
+    fn complete(&mut self) -> Completion<Self::Value, Error<S>> {
+        let source = self.0.take().expect("probe already taken");
+        source.into_completion().map_abrupt(ResidualError::into_error)
+    }
+}
+
+impl<'a, 'b, S> LoopConvert for &'b mut Probe<'a, Option<S>> {
+    type Source = S;
+
+    fn complete<E>(&mut self)
+        -> Completion<<S as IntoCompletion<Result<Infallible, E>>>::Value, E>
+        where S: IntoCompletion<Result<Infallible, E>>
+    {
+        let source = self.0.take().expect("probe already taken");
+        source.into_completion().map_abrupt(ResidualError::into_error)
+    }
+}
+
+/// Invokes the handler of `try_continue!` and `try_break!`.
+///
+/// Passing the handler through this function lets a closure infer its
+/// parameter from the error.
+pub fn handle_error<E, H: FnOnce(E)>(err: E, handler: H) {
+    handler(err)
+}
+
+/// Adds the error of a residual to the errors collected by `first_ok!`.
+pub fn push_error<R: ResidualError>(errors: &mut Option<Errors<R::Error>>, residual: R) {
+    let error = residual.into_error();
+    match *errors {
+        Some(ref mut errors) => errors.push(error),
+        None => *errors = Some(Errors::new(error)),
+    }
+}
+
+/// Converts the errors collected by `first_ok!` into a residual.
+pub fn all_failed<E>(errors: Option<Errors<E>>) -> Validated<Infallible, E> {
+    Validated::Invalid(errors.expect("no errors collected"))
+}
+
+/// Returns a value of the type of the given reference.
+///
+/// This is used in a dead branch to pin down the type of an abrupt value
+/// to the return type of the function before it's inspected.
+pub fn same_type<T>(_value: &T) -> T {
+    unreachable!()
+}
+
+/// Returns the label of a `try!` invocation if one was given.
+pub const fn label(labels: &[&'static str]) -> Option<&'static str> {
+    if labels.is_empty() {
+        None
+    } else {
+        Some(labels[0])
+    }
+}
+
+/// Wraps an abrupt value for autoref based dispatch on its type.
+///
+/// Traits implemented for `Probe<R>` take precedence over the fallback
+/// traits implemented for `&mut Probe<R>`.
+pub struct Probe<'a, R: 'a>(pub &'a mut R);
+
+/// Converts the expression of a `try!` invocation into a completion and
+/// reports a residual that implements `Debug` to the abrupt hook.
+///
+/// The type of the expression is recorded in `source_type` for
+/// `TraceProbe`.
+pub trait ReportResidual {
+    type Source: Branch;
+    fn complete<R>(&mut self, site: &'static CallSite, source_type: &PhantomData<Self::Source>)
+        -> Completion<<Self::Source as Branch>::Value, R>
+        where R: FromResidual<<Self::Source as Branch>::Residual>;
+}
+
+/// Fallback that reports an abrupt expression without a `Debug` residual
+/// by the type name of the expression.
+pub trait ReportOpaque {
+    type Source;
+    fn complete<R>(&mut self, site: &'static CallSite, source_type: &PhantomData<Self::Source>)
+        -> Completion<<Self::Source as IntoCompletion<R>>::Value, R>
+        where Self::Source: IntoCompletion<R>;
+}
+
+#[cfg(feature = "hooks")]
+impl<'a, S> ReportResidual for Probe<'a, Option<S>>
+    where S: Branch, S::Residual: fmt::Debug
+{
+    type Source = S;
+
+    fn complete<R>(&mut self, site: &'static CallSite, _source_type: &PhantomData<S>)
+        -> Completion<S::Value, R>
+        where R: FromResidual<S::Residual>
+    {
+        let source = self.0.take().expect("probe already taken");
+        source.branch().map_abrupt(|residual| {
+            if let Some(hook) = hook::abrupt_hook() {
+                hook(site, &residual);
+            }
+            convert(site, || R::from_residual(residual))
+        })
+    }
+}
+
+impl<'a, 'b, S> ReportOpaque for &'b mut Probe<'a, Option<S>> {
+    type Source = S;
+
+    fn complete<R>(&mut self, site: &'static CallSite, _source_type: &PhantomData<S>)
+        -> Completion<S::Value, R>
+        where S: IntoCompletion<R>
+    {
+        let source = self.0.take().expect("probe already taken");
+        let completion = convert(site, || source.into_completion());
+        #[cfg(feature = "hooks")]
+        {
+            if let (&Completion::Abrupt(_), Some(hook)) = (&completion, hook::abrupt_hook()) {
+                hook(site, &Opaque(any::type_name::<S>()));
+            }
+        }
+        completion
+    }
+}
+
+/// Runs the conversion of a `try!` expression.
+///
+/// A `Traced` created by the conversion records the call site.
+#[cfg(feature = "trace")]
+fn convert<F: FnOnce() -> R, R>(site: &'static CallSite, f: F) -> R {
+    trace::converting(site, f)
+}
+
+/// Runs the conversion of a `try!` expression.
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+fn convert<F: FnOnce() -> R, R>(_site: &'static CallSite, f: F) -> R {
+    f()
+}
+
+#[cfg(feature = "hooks")]
+struct Opaque(&'static str);
+
+#[cfg(feature = "hooks")]
+impl fmt::Debug for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}>", self.0)
+    }
+}
+
+/// Replaces the value of a selected `try!` expression with a fault.
+#[cfg(feature = "fault-injection")]
+pub trait InjectFault {
+    type Output;
+    fn inject_fault(&mut self, site: &'static CallSite) -> Self::Output;
+}
+
+/// Fallback for expressions that cannot hold an injected fault.
+#[cfg(feature = "fault-injection")]
+pub trait RejectFault {
+    type Output;
+    fn inject_fault(&mut self, site: &'static CallSite) -> Self::Output;
+}
+
+#[cfg(feature = "fault-injection")]
+impl<'a, T, E: 'static> InjectFault for Probe<'a, Option<Result<T, E>>> {
+    type Output = Result<T, E>;
+
+    fn inject_fault(&mut self, site: &'static CallSite) -> Result<T, E> {
+        let source = self.0.take().expect("probe already taken");
+        match fault::take(site) {
+            Some(err) => Err(err),
+            None => source,
+        }
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+impl<'a, T> InjectFault for Probe<'a, Option<Option<T>>> {
+    type Output = Option<T>;
+
+    fn inject_fault(&mut self, site: &'static CallSite) -> Option<T> {
+        let source = self.0.take().expect("probe already taken");
+        match fault::take(site) {
+            Some(NoneError) => None,
+            None => source,
+        }
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+impl<'a, 'b, S> RejectFault for &'b mut Probe<'a, Option<S>> {
+    type Output = S;
+
+    fn inject_fault(&mut self, site: &'static CallSite) -> S {
+        fault::reject(site, any::type_name::<S>());
+        self.0.take().expect("probe already taken")
+    }
+}
+
+/// Offers the error of a result to the condition handlers.
+#[cfg(feature = "conditions")]
+pub fn signal_condition<T: 'static, E: Condition>(result: Result<T, E>) -> Result<T, E> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(err) => match condition::signal(&err) {
+            Some(value) => Ok(value),
+            None => Err(err),
+        },
+    }
+}
+
+/// Moves the warnings of an `Outcome` into the accumulator of `outcome!`.
+pub trait MergeWarnings<V> {
+    type Output;
+    fn merge_warnings(&mut self, warnings: &mut Vec<V>) -> Self::Output;
+}
+
+/// Fallback for values that are not an `Outcome`.
+pub trait KeepWarnings<V> {
+    type Output;
+    fn merge_warnings(&mut self, warnings: &mut Vec<V>) -> Self::Output;
+}
+
+impl<'a, T, W, E, V: From<W>> MergeWarnings<V> for Probe<'a, Option<Outcome<T, W, E>>> {
+    type Output = Result<T, E>;
+
+    fn merge_warnings(&mut self, warnings: &mut Vec<V>) -> Result<T, E> {
+        self.0.take().expect("probe already taken").drain_warnings(warnings)
+    }
+}
+
+impl<'a, 'b, S, V> KeepWarnings<V> for &'b mut Probe<'a, Option<S>> {
+    type Output = S;
+
+    fn merge_warnings(&mut self, _warnings: &mut Vec<V>) -> S {
+        self.0.take().expect("probe already taken")
+    }
+}
+
+/// Wraps an abrupt value and the type of the `try!` expression it came
+/// from for autoref based dispatch.
+pub struct TraceProbe<'a, R: 'a, S>(pub &'a mut R, pub PhantomData<S>);
+
+/// Records the call site into a propagated `Traced` error.
+pub trait RecordTrace {
+    fn record_trace(&mut self, site: &'static CallSite);
+}
+
+/// Fallback for abrupt values that do not propagate a `Traced` error.
+pub trait SkipTrace {
+    fn record_trace(&mut self, site: &'static CallSite);
+}
+
+#[cfg(feature = "trace")]
+impl<'a, T, U, E> RecordTrace for TraceProbe<'a, Result<T, Traced<E>>, Result<U, Traced<E>>> {
+    fn record_trace(&mut self, site: &'static CallSite) {
+        if let Err(ref mut err) = *self.0 {
+            err.trace_mut().push(site);
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl<'a, T, U, E> RecordTrace
+    for TraceProbe<'a, Option<Result<T, Traced<E>>>, Result<U, Traced<E>>>
+{
+    fn record_trace(&mut self, site: &'static CallSite) {
+        if let Some(Err(ref mut err)) = *self.0 {
+            err.trace_mut().push(site);
+        }
+    }
+}
+
+impl<'a, 'b, R, S> SkipTrace for &'b mut TraceProbe<'a, R, S> {
+    #[inline(always)]
+    fn record_trace(&mut self, _site: &'static CallSite) {}
+}
+
+/// Counts the completions of a `try!` invocation for the `stats` feature.
+#[cfg(feature = "stats")]
+pub struct SiteCounter {
+    site: &'static CallSite,
+    values: AtomicU64,
+    abrupts: AtomicU64,
+    registered: AtomicBool,
+}
+
+/// Counts the completions of a `try!` invocation for the `stats` feature.
+#[cfg(not(feature = "stats"))]
+pub struct SiteCounter;
+
+#[cfg(feature = "stats")]
+impl SiteCounter {
+    pub const fn new(site: &'static CallSite) -> SiteCounter {
+        SiteCounter {
+            site,
+            values: AtomicU64::new(0),
+            abrupts: AtomicU64::new(0),
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    pub fn count_value(&'static self) {
+        self.values.fetch_add(1, Ordering::Relaxed);
+        self.register();
+    }
+
+    pub fn count_abrupt(&'static self) {
+        self.abrupts.fetch_add(1, Ordering::Relaxed);
+        self.register();
+    }
+
+    fn register(&'static self) {
+        if !self.registered.load(Ordering::Relaxed) &&
+           !self.registered.swap(true, Ordering::AcqRel) {
+            stats::register(self);
+        }
+    }
+
+    pub(crate) fn site(&self) -> &'static CallSite {
+        self.site
+    }
+
+    pub(crate) fn values(&self) -> u64 {
+        self.values.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn abrupts(&self) -> u64 {
+        self.abrupts.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn reset(&self) {
+        self.values.store(0, Ordering::Relaxed);
+        self.abrupts.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(feature = "stats"))]
+impl SiteCounter {
+    pub const fn new(_site: &'static CallSite) -> SiteCounter {
+        SiteCounter
+    }
+
+    #[inline(always)]
+    pub fn count_value(&'static self) {}
+
+    #[inline(always)]
+    pub fn count_abrupt(&'static self) {}
+}
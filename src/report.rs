@@ -0,0 +1,155 @@
+//! Rendering of errors for humans and log pipelines.
+//!
+//! A `Report` renders an error together with the chain of its sources
+//! and the locations it propagated through.  It can be rendered as text
+//! through `Display` or as a JSON document through `to_json`:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! use carrier::report::Report;
+//!
+//! fn load(s: &str) -> Result<i32, carrier::Context<std::num::ParseIntError>> {
+//!     Ok(try!(s.parse::<i32>(); "loading {:?}", s))
+//! }
+//!
+//! # fn main() {
+//! let err = load("x").unwrap_err();
+//! assert_eq!(Report::new(&err).to_string(), "\
+//! Error: loading \"x\"
+//!
+//! Caused by:
+//!     0: invalid digit found in string");
+//! # }
+//! ```
+//!
+//! The locations are taken from the trace of a `trace::Traced` error or
+//! can be supplied explicitly with `with_locations`.  The output only
+//! depends on the error, backtraces are never included, so it's suitable
+//! for snapshot tests.
+use std::error::Error as StdError;
+use std::fmt;
+
+use json;
+#[cfg(feature = "trace")]
+use trace::Traced;
+use {CallSite, Error};
+
+/// Renders an error with its sources and locations.
+#[derive(Debug, Clone, Copy)]
+pub struct Report<'a> {
+    error: &'a (dyn StdError + 'static),
+    locations: &'a [&'static CallSite],
+}
+
+/// An iterator over the causes of a reported error.
+///
+/// This is returned by `Report::causes`.
+pub struct Causes<'a> {
+    next: Option<&'a (dyn StdError + 'static)>,
+}
+
+impl<'a> Report<'a> {
+    /// Creates a report for an error without locations.
+    pub fn new(error: &'a (dyn StdError + 'static)) -> Report<'a> {
+        Report {
+            error,
+            locations: &[],
+        }
+    }
+
+    /// Sets the locations the error propagated through.
+    pub fn with_locations(mut self, locations: &'a [&'static CallSite]) -> Report<'a> {
+        self.locations = locations;
+        self
+    }
+
+    /// The reported error.
+    pub fn error(&self) -> &'a (dyn StdError + 'static) {
+        self.error
+    }
+
+    /// Iterates over the sources of the reported error.
+    pub fn causes(&self) -> Causes<'a> {
+        Causes {
+            next: self.error.source(),
+        }
+    }
+
+    /// The locations the error propagated through.
+    pub fn locations(&self) -> &'a [&'static CallSite] {
+        self.locations
+    }
+
+    /// Renders the report as a JSON document.
+    ///
+    /// The document is an object with the message of the error as
+    /// `error`, the messages of its sources as `causes` and the locations
+    /// as `locations`.
+    pub fn to_json(&self) -> String {
+        let mut rv = String::from("{\"error\":");
+        json::write_str(&mut rv, &self.error.to_string());
+        rv.push_str(",\"causes\":[");
+        for (idx, cause) in self.causes().enumerate() {
+            if idx > 0 {
+                rv.push(',');
+            }
+            json::write_str(&mut rv, &cause.to_string());
+        }
+        rv.push_str("],\"locations\":[");
+        for (idx, site) in self.locations.iter().enumerate() {
+            if idx > 0 {
+                rv.push(',');
+            }
+            rv.push('{');
+            json::write_site_fields(&mut rv, site);
+            rv.push('}');
+        }
+        rv.push_str("]}");
+        rv
+    }
+}
+
+impl<'a> From<&'a Error> for Report<'a> {
+    fn from(error: &'a Error) -> Report<'a> {
+        Report::new(error.as_ref())
+    }
+}
+
+#[cfg(feature = "trace")]
+impl<'a, E: StdError + 'static> From<&'a Traced<E>> for Report<'a> {
+    fn from(error: &'a Traced<E>) -> Report<'a> {
+        Report::new(error).with_locations(error.trace().frames())
+    }
+}
+
+impl<'a> fmt::Display for Report<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Error: {}", self.error)?;
+        for (idx, cause) in self.causes().enumerate() {
+            if idx == 0 {
+                write!(f, "\n\nCaused by:")?;
+            }
+            write!(f, "\n{:>5}: {}", idx, cause)?;
+        }
+        for (idx, site) in self.locations.iter().enumerate() {
+            if idx == 0 {
+                write!(f, "\n\nLocations:")?;
+            }
+            match site.expression() {
+                Some(expr) => write!(f, "\n{:>5}: `{}` failed at {}", idx, expr, site)?,
+                None => write!(f, "\n{:>5}: {} at {}", idx, site.module_path(), site)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for Causes<'a> {
+    type Item = &'a (dyn StdError + 'static);
+
+    fn next(&mut self) -> Option<&'a (dyn StdError + 'static)> {
+        let rv = self.next.take()?;
+        self.next = rv.source();
+        Some(rv)
+    }
+}
@@ -0,0 +1,75 @@
+//! Call site information for `try!` invocations.
+use std::fmt;
+
+/// The location of a `try!` invocation.
+///
+/// Call sites are recorded by the instrumentation features of this crate
+/// (for instance the traces of the `trace` feature).  They are always
+/// `'static` as every `try!` invocation places its call site into a
+/// static.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallSite {
+    file: &'static str,
+    line: u32,
+    column: u32,
+    module_path: &'static str,
+    expression: Option<&'static str>,
+    label: Option<&'static str>,
+}
+
+impl CallSite {
+    #[doc(hidden)]
+    pub const fn __new(file: &'static str, line: u32, column: u32,
+                       module_path: &'static str,
+                       expression: Option<&'static str>,
+                       label: Option<&'static str>) -> CallSite {
+        CallSite {
+            file,
+            line,
+            column,
+            module_path,
+            expression,
+            label,
+        }
+    }
+
+    /// The file that contains the `try!` invocation.
+    pub fn file(&self) -> &'static str {
+        self.file
+    }
+
+    /// The line of the `try!` invocation.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column of the `try!` invocation.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// The path of the module that contains the `try!` invocation.
+    pub fn module_path(&self) -> &'static str {
+        self.module_path
+    }
+
+    /// The source text of the expression passed to `try!`.
+    ///
+    /// This is only recorded if the `trace-expr` feature is enabled.
+    pub fn expression(&self) -> Option<&'static str> {
+        self.expression
+    }
+
+    /// The label given to the `try!` invocation.
+    ///
+    /// A label is given as `try!(#"label" expr)`.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+}
+
+impl fmt::Display for CallSite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
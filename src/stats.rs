@@ -0,0 +1,166 @@
+//! Per call site completion statistics.
+//!
+//! With the `stats` feature enabled every `try!` invocation counts how
+//! often it completed with a value and how often it propagated an abrupt
+//! completion.  A call site is registered the first time it is evaluated,
+//! and `snapshot` returns the counts of all registered call sites:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! fn parse(s: &str) -> Result<i32, std::num::ParseIntError> {
+//!     Ok(try!(s.parse::<i32>()))
+//! }
+//!
+//! # fn main() {
+//! parse("42").ok();
+//! parse("x").ok();
+//! let mut snapshot = carrier::stats::snapshot();
+//! snapshot.sort_by_abrupts();
+//! println!("{}", snapshot);
+//! # }
+//! ```
+//!
+//! A snapshot can be rendered as a text table through `Display` or as JSON
+//! through `to_json`.
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use __private::SiteCounter;
+use json;
+use CallSite;
+
+static REGISTRY: Mutex<Vec<&'static SiteCounter>> = Mutex::new(Vec::new());
+
+pub(crate) fn register(counter: &'static SiteCounter) {
+    REGISTRY.lock().unwrap_or_else(|err| err.into_inner()).push(counter);
+}
+
+/// The counts of a single call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SiteStats {
+    site: &'static CallSite,
+    values: u64,
+    abrupts: u64,
+}
+
+/// The counts of all call sites at a point in time.
+///
+/// The call sites are in registration order unless sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    sites: Vec<SiteStats>,
+}
+
+/// Returns the current counts of all registered call sites.
+pub fn snapshot() -> Snapshot {
+    let registry = REGISTRY.lock().unwrap_or_else(|err| err.into_inner());
+    Snapshot {
+        sites: registry.iter().map(|counter| SiteStats {
+            site: counter.site(),
+            values: counter.values(),
+            abrupts: counter.abrupts(),
+        }).collect(),
+    }
+}
+
+/// Resets the counts of all registered call sites to zero.
+pub fn reset() {
+    for counter in REGISTRY.lock().unwrap_or_else(|err| err.into_inner()).iter() {
+        counter.reset();
+    }
+}
+
+impl SiteStats {
+    /// The call site the counts belong to.
+    pub fn site(&self) -> &'static CallSite {
+        self.site
+    }
+
+    /// How often the call site completed with a value.
+    pub fn values(&self) -> u64 {
+        self.values
+    }
+
+    /// How often the call site propagated an abrupt completion.
+    pub fn abrupts(&self) -> u64 {
+        self.abrupts
+    }
+
+    /// How often the call site was evaluated.
+    pub fn total(&self) -> u64 {
+        self.values + self.abrupts
+    }
+}
+
+impl Snapshot {
+    /// The counts of the individual call sites.
+    pub fn sites(&self) -> &[SiteStats] {
+        &self.sites
+    }
+
+    /// Returns the counts of the given call site.
+    pub fn get(&self, site: &CallSite) -> Option<&SiteStats> {
+        self.sites.iter().find(|stats| stats.site == site)
+    }
+
+    /// Keeps only the call sites for which the predicate returns `true`.
+    pub fn retain<F>(&mut self, f: F)
+        where F: FnMut(&SiteStats) -> bool
+    {
+        self.sites.retain(f);
+    }
+
+    /// Sorts the call sites with the given comparator.
+    pub fn sort_by<F>(&mut self, compare: F)
+        where F: FnMut(&SiteStats, &SiteStats) -> Ordering
+    {
+        self.sites.sort_by(compare);
+    }
+
+    /// Sorts the call sites by file, line and column.
+    pub fn sort_by_site(&mut self) {
+        self.sites.sort_by_key(|stats| stats.site);
+    }
+
+    /// Sorts the call sites by abrupt count, most abrupt first.
+    pub fn sort_by_abrupts(&mut self) {
+        self.sites.sort_by(|a, b| b.abrupts.cmp(&a.abrupts).then(a.site.cmp(b.site)));
+    }
+
+    /// Sorts the call sites by evaluation count, most evaluated first.
+    pub fn sort_by_total(&mut self) {
+        self.sites.sort_by(|a, b| b.total().cmp(&a.total()).then(a.site.cmp(b.site)));
+    }
+
+    /// Renders the snapshot as a JSON array.
+    ///
+    /// Every call site is an object with the keys `file`, `line`,
+    /// `column`, `module_path`, `expression`, `label`, `values` and
+    /// `abrupts`.
+    pub fn to_json(&self) -> String {
+        let mut rv = String::from("[");
+        for (idx, stats) in self.sites.iter().enumerate() {
+            if idx > 0 {
+                rv.push(',');
+            }
+            rv.push('{');
+            json::write_site_fields(&mut rv, stats.site);
+            write!(rv, ",\"values\":{},\"abrupts\":{}}}",
+                   stats.values, stats.abrupts).unwrap();
+        }
+        rv.push(']');
+        rv
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>10} {:>10}  site", "values", "abrupts")?;
+        for stats in &self.sites {
+            write!(f, "\n{:>10} {:>10}  {}", stats.values, stats.abrupts, stats.site)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! Propagation traces for abrupt completions.
+//!
+//! With the `trace` feature enabled every `try!` that propagates an error
+//! wrapped in a `Traced` records its call site into it.  This results in
+//! a "return trace": the list of all `try!` invocations the error passed
+//! through on its way up, starting with the one where it first went
+//! abrupt.
+//!
+//! Errors are wrapped automatically as `Traced<E>` implements `From<E>`,
+//! so a function only has to declare `Traced<E>` as its error type:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! # use carrier::trace::Traced;
+//! fn parse(s: &str) -> Result<i32, Traced<std::num::ParseIntError>> {
+//!     Ok(try!(s.parse::<i32>()))
+//! }
+//!
+//! fn parse_twice(s: &str) -> Result<i32, Traced<std::num::ParseIntError>> {
+//!     Ok(try!(parse(s)) * 2)
+//! }
+//! # fn main() {
+//! let err = parse_twice("x").unwrap_err();
+//! assert_eq!(err.trace().frames().len(), 2);
+//! # }
+//! ```
+//!
+//! With the `trace-expr` feature the source text of the expression passed
+//! to `try!` is recorded as well and shown when the trace is displayed:
+//!
+//! ```text
+//!    0: `File::open(&path)` failed at src/config.rs:42:5
+//!    1: `load_config()` failed at src/main.rs:12:18
+//! ```
+//!
+//! The call site where an error went abrupt is recorded when the error
+//! is wrapped into a `Traced` by the conversion of the `try!`.  Every
+//! further `try!` that propagates a `Result` holding a `Traced` into a
+//! `Result` (or `Option<Result>`) with the same `Traced` error records
+//! its call site into the propagated error, also if the error was stored
+//! or moved to another thread in between.
+//!
+//! The types involved are inspected while they are still being inferred.
+//! For a `try!` that propagates into a `Traced` error the error type of
+//! the expression has to be known at the `try!`, so `s.parse::<i32>()`
+//! has to be spelled out where `s.parse()` would be inferred later.  The
+//! same goes for a `try!` in a closure or block whose return type is only
+//! inferred after the `try!`, such as a closure ending in `Ok(x)`.
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+
+use CallSite;
+
+thread_local! {
+    static CONVERTING: Cell<Option<&'static CallSite>> = const { Cell::new(None) };
+}
+
+/// The call sites an abrupt completion propagated through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    frames: Vec<&'static CallSite>,
+}
+
+/// An error with a propagation trace attached.
+///
+/// Displaying a `Traced` only displays the wrapped error, the trace itself
+/// is available through `trace()`.  The error is otherwise transparent,
+/// its `source()` is the source of the wrapped error.
+#[derive(Debug, Clone)]
+pub struct Traced<E> {
+    error: E,
+    trace: Trace,
+}
+
+impl Trace {
+    /// Creates an empty trace.
+    pub fn new() -> Trace {
+        Trace::default()
+    }
+
+    /// Records a call site in the trace.
+    pub fn push(&mut self, site: &'static CallSite) {
+        self.frames.push(site);
+    }
+
+    /// The recorded call sites, starting with the innermost one.
+    pub fn frames(&self) -> &[&'static CallSite] {
+        &self.frames
+    }
+
+    /// Returns `true` if no call site was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, site) in self.frames.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            match site.expression() {
+                Some(expr) => write!(f, "{:>4}: `{}` failed at {}", idx, expr, site)?,
+                None => write!(f, "{:>4}: {} at {}", idx, site.module_path(), site)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E> Traced<E> {
+    /// Wraps an error with an empty trace.
+    ///
+    /// If the error is wrapped by the conversion of a `try!` invocation,
+    /// the call site of the invocation becomes the first frame.
+    pub fn new(error: E) -> Traced<E> {
+        let mut trace = Trace::new();
+        if let Some(site) = CONVERTING.try_with(|site| site.take()).ok().and_then(|x| x) {
+            trace.push(site);
+        }
+        Traced {
+            error,
+            trace,
+        }
+    }
+
+    /// Returns a reference to the wrapped error.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Unwraps the error and discards the trace.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+
+    /// Returns the propagation trace.
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// Returns a mutable reference to the propagation trace.
+    pub fn trace_mut(&mut self) -> &mut Trace {
+        &mut self.trace
+    }
+}
+
+impl<E> From<E> for Traced<E> {
+    fn from(error: E) -> Traced<E> {
+        Traced::new(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Traced<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<E: Error> Error for Traced<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Runs the conversion of a `try!` invocation at the given call site.
+///
+/// A `Traced` created by the conversion records the call site.
+pub(crate) fn converting<F: FnOnce() -> R, R>(site: &'static CallSite, f: F) -> R {
+    struct Restore(Option<&'static CallSite>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0;
+            CONVERTING.try_with(|current| current.set(outer)).ok();
+        }
+    }
+
+    let outer = CONVERTING.try_with(|current| current.replace(Some(site)));
+    let _restore = outer.ok().map(Restore);
+    f()
+}
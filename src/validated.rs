@@ -0,0 +1,395 @@
+//! Accumulating validation.
+//!
+//! `try!` stops at the first abrupt completion.  `Validated` instead
+//! collects all errors, which is useful for validating forms or config
+//! files where the user wants to see every problem at once.  The
+//! `validate!` macro evaluates several sources and produces either all of
+//! their values or all of their errors:
+//!
+//! ```rust
+//! # #[macro_use] extern crate carrier;
+//! use carrier::Validated;
+//!
+//! fn parse_point(x: &str, y: &str) -> Validated<(i32, i32), std::num::ParseIntError> {
+//!     validate!(x.parse::<i32>(), y.parse::<i32>())
+//! }
+//!
+//! # fn main() {
+//! assert_eq!(parse_point("1", "2").valid(), Some((1, 2)));
+//! assert_eq!(parse_point("x", "y").errors().map(|x| x.len()), Some(2));
+//! # }
+//! ```
+//!
+//! The sources are converted through the regular `IntoCompletion` rules,
+//! so results, options and other carriers can be mixed as long as their
+//! errors convert into the error type with `Into`.
+//!
+//! A `Validated` also works with `try!` itself.  Within a function
+//! returning a `Result<T, Vec<E>>` an invalid value propagates all of its
+//! errors.
+use std::convert::Infallible;
+use std::iter::FromIterator;
+use std::slice;
+use std::vec;
+
+use {Branch, Completion, FromResidual, FromValue, IntoCompletion, NoneError, ResidualError};
+
+/// A value or a non-empty list of errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Validated<T, E> {
+    /// All validations passed.
+    Valid(T),
+    /// At least one validation failed.
+    Invalid(Errors<E>),
+}
+
+/// A non-empty list of errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Errors<E> {
+    first: E,
+    rest: Vec<E>,
+}
+
+impl<E> Errors<E> {
+    /// Creates a list with a single error.
+    pub fn new(first: E) -> Errors<E> {
+        Errors {
+            first,
+            rest: Vec::new(),
+        }
+    }
+
+    /// Creates a list from a vector, returning `None` if it's empty.
+    pub fn from_vec(mut errors: Vec<E>) -> Option<Errors<E>> {
+        if errors.is_empty() {
+            return None;
+        }
+        let first = errors.remove(0);
+        Some(Errors { first, rest: errors })
+    }
+
+    /// The first error.
+    pub fn first(&self) -> &E {
+        &self.first
+    }
+
+    /// The number of errors.
+    pub fn len(&self) -> usize {
+        self.rest.len() + 1
+    }
+
+    /// Always returns `false` as the list is never empty.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Appends an error.
+    pub fn push(&mut self, error: E) {
+        self.rest.push(error);
+    }
+
+    /// Appends all errors of another list.
+    pub fn append(&mut self, other: Errors<E>) {
+        self.rest.push(other.first);
+        self.rest.extend(other.rest);
+    }
+
+    /// Iterates over the errors.
+    pub fn iter(&self) -> Iter<'_, E> {
+        Iter {
+            first: Some(&self.first),
+            rest: self.rest.iter(),
+        }
+    }
+
+    /// Converts all errors with a function.
+    pub fn map<F, O: FnMut(E) -> F>(self, mut op: O) -> Errors<F> {
+        Errors {
+            first: op(self.first),
+            rest: self.rest.into_iter().map(op).collect(),
+        }
+    }
+
+    /// Converts the list into a vector.
+    pub fn into_vec(self) -> Vec<E> {
+        let mut rv = Vec::with_capacity(self.len());
+        rv.push(self.first);
+        rv.extend(self.rest);
+        rv
+    }
+}
+
+/// An iterator over references to the errors of an `Errors`.
+pub struct Iter<'a, E: 'a> {
+    first: Option<&'a E>,
+    rest: slice::Iter<'a, E>,
+}
+
+impl<'a, E> Iterator for Iter<'a, E> {
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<&'a E> {
+        self.first.take().or_else(|| self.rest.next())
+    }
+}
+
+impl<'a, E> IntoIterator for &'a Errors<E> {
+    type Item = &'a E;
+    type IntoIter = Iter<'a, E>;
+
+    fn into_iter(self) -> Iter<'a, E> {
+        self.iter()
+    }
+}
+
+impl<E> IntoIterator for Errors<E> {
+    type Item = E;
+    type IntoIter = vec::IntoIter<E>;
+
+    fn into_iter(self) -> vec::IntoIter<E> {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<T, E> Validated<T, E> {
+    /// Evaluates a source through the `IntoCompletion` rules.
+    ///
+    /// This is used by the `validate!` macro for each of its sources.
+    pub fn from_source<S>(source: S) -> Validated<S::Value, E>
+        where S: IntoCompletion<Errors<E>, Value = T>
+    {
+        match source.into_completion() {
+            Completion::Value(value) => Validated::Valid(value),
+            Completion::Abrupt(errors) => Validated::Invalid(errors),
+        }
+    }
+
+    /// Returns `true` if this is a valid value.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Validated::Valid(..) => true,
+            Validated::Invalid(..) => false,
+        }
+    }
+
+    /// Returns `true` if this holds errors.
+    pub fn is_invalid(&self) -> bool {
+        !self.is_valid()
+    }
+
+    /// Returns the value if valid.
+    pub fn valid(self) -> Option<T> {
+        match self {
+            Validated::Valid(value) => Some(value),
+            Validated::Invalid(..) => None,
+        }
+    }
+
+    /// Returns the errors if invalid.
+    pub fn errors(&self) -> Option<&Errors<E>> {
+        match *self {
+            Validated::Valid(..) => None,
+            Validated::Invalid(ref errors) => Some(errors),
+        }
+    }
+
+    /// Maps the value with a function.
+    pub fn map<U, O: FnOnce(T) -> U>(self, op: O) -> Validated<U, E> {
+        match self {
+            Validated::Valid(value) => Validated::Valid(op(value)),
+            Validated::Invalid(errors) => Validated::Invalid(errors),
+        }
+    }
+
+    /// Maps every error with a function.
+    pub fn map_errors<F, O: FnMut(E) -> F>(self, op: O) -> Validated<T, F> {
+        match self {
+            Validated::Valid(value) => Validated::Valid(value),
+            Validated::Invalid(errors) => Validated::Invalid(errors.map(op)),
+        }
+    }
+
+    /// Combines two validations, accumulating the errors of both.
+    pub fn and<U>(self, other: Validated<U, E>) -> Validated<(T, U), E> {
+        match (self, other) {
+            (Validated::Valid(a), Validated::Valid(b)) => Validated::Valid((a, b)),
+            (Validated::Valid(..), Validated::Invalid(errors)) |
+            (Validated::Invalid(errors), Validated::Valid(..)) => Validated::Invalid(errors),
+            (Validated::Invalid(mut errors), Validated::Invalid(other)) => {
+                errors.append(other);
+                Validated::Invalid(errors)
+            }
+        }
+    }
+
+    /// Converts the validation into a result with all errors.
+    pub fn into_result(self) -> Result<T, Vec<E>> {
+        match self {
+            Validated::Valid(value) => Ok(value),
+            Validated::Invalid(errors) => Err(errors.into_vec()),
+        }
+    }
+}
+
+impl<T, E> From<Validated<T, E>> for Result<T, Vec<E>> {
+    fn from(validated: Validated<T, E>) -> Result<T, Vec<E>> {
+        validated.into_result()
+    }
+}
+
+impl<T, E, C: FromIterator<T>> FromIterator<Validated<T, E>> for Validated<C, E> {
+    fn from_iter<I: IntoIterator<Item = Validated<T, E>>>(iter: I) -> Validated<C, E> {
+        let mut values = vec![];
+        let mut rv: Option<Errors<E>> = None;
+        for item in iter {
+            match (item, &mut rv) {
+                (Validated::Valid(value), _) => values.push(value),
+                (Validated::Invalid(errors), &mut Some(ref mut rv)) => rv.append(errors),
+                (Validated::Invalid(errors), rv) => *rv = Some(errors),
+            }
+        }
+        match rv {
+            Some(errors) => Validated::Invalid(errors),
+            None => Validated::Valid(values.into_iter().collect()),
+        }
+    }
+}
+
+impl<T, E> Branch for Validated<T, E> {
+    type Value = T;
+    type Residual = Validated<Infallible, E>;
+
+    fn branch(self) -> Completion<T, Validated<Infallible, E>> {
+        match self {
+            Validated::Valid(value) => Completion::Value(value),
+            Validated::Invalid(errors) => Completion::Abrupt(Validated::Invalid(errors)),
+        }
+    }
+}
+
+impl<E> ResidualError for Validated<Infallible, E> {
+    type Error = Errors<E>;
+
+    fn into_error(self) -> Errors<E> {
+        match self {
+            Validated::Valid(never) => match never {},
+            Validated::Invalid(errors) => errors,
+        }
+    }
+}
+
+impl<T, E, F> FromResidual<Validated<Infallible, E>> for Validated<T, F>
+    where E: Into<F>
+{
+    fn from_residual(residual: Validated<Infallible, E>) -> Validated<T, F> {
+        Validated::Invalid(residual.into_error().map(Into::into))
+    }
+}
+
+impl<T, E, F> FromResidual<Result<Infallible, E>> for Validated<T, F>
+    where E: Into<F>
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Validated<T, F> {
+        Validated::Invalid(Errors::new(residual.into_error().into()))
+    }
+}
+
+impl<T, F> FromResidual<Option<Infallible>> for Validated<T, F>
+    where NoneError: Into<F>
+{
+    fn from_residual(_residual: Option<Infallible>) -> Validated<T, F> {
+        Validated::Invalid(Errors::new(NoneError.into()))
+    }
+}
+
+impl<T, E, F> FromResidual<Validated<Infallible, E>> for Result<T, Vec<F>>
+    where E: Into<F>
+{
+    fn from_residual(residual: Validated<Infallible, E>) -> Result<T, Vec<F>> {
+        Err(residual.into_error().map(Into::into).into_vec())
+    }
+}
+
+impl<E, F> FromResidual<Validated<Infallible, E>> for Errors<F>
+    where E: Into<F>
+{
+    fn from_residual(residual: Validated<Infallible, E>) -> Errors<F> {
+        residual.into_error().map(Into::into)
+    }
+}
+
+impl<E, F> FromResidual<Result<Infallible, E>> for Errors<F>
+    where E: Into<F>
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Errors<F> {
+        Errors::new(residual.into_error().into())
+    }
+}
+
+impl<F> FromResidual<Option<Infallible>> for Errors<F>
+    where NoneError: Into<F>
+{
+    fn from_residual(_residual: Option<Infallible>) -> Errors<F> {
+        Errors::new(NoneError.into())
+    }
+}
+
+impl<T, E> FromValue<T> for Validated<T, E> {
+    fn from_value(value: T) -> Validated<T, E> {
+        Validated::Valid(value)
+    }
+}
+
+/// Combines a tuple of validations into a validation of a tuple.
+///
+/// This is implemented for tuples of up to twelve `Validated` values with
+/// the same error type and used by the `validate!` macro.
+pub trait Zip {
+    /// The resulting validation.
+    type Output;
+
+    /// Combines the validations, accumulating all errors.
+    fn zip(self) -> Self::Output;
+}
+
+macro_rules! impl_zip {
+    ($($name:ident $var:ident),+) => {
+        impl<$($name,)+ E> Zip for ($(Validated<$name, E>,)+) {
+            type Output = Validated<($($name,)+), E>;
+
+            fn zip(self) -> Validated<($($name,)+), E> {
+                let ($($var,)+) = self;
+                let mut errors: Option<Errors<E>> = None;
+                $(
+                    let $var = match $var {
+                        Validated::Valid(value) => Some(value),
+                        Validated::Invalid(other) => {
+                            match errors {
+                                Some(ref mut errors) => errors.append(other),
+                                None => errors = Some(other),
+                            }
+                            None
+                        }
+                    };
+                )+
+                match errors {
+                    Some(errors) => Validated::Invalid(errors),
+                    None => Validated::Valid(($($var.unwrap(),)+)),
+                }
+            }
+        }
+    }
+}
+
+impl_zip!(A a);
+impl_zip!(A a, B b);
+impl_zip!(A a, B b, C c);
+impl_zip!(A a, B b, C c, D d);
+impl_zip!(A a, B b, C c, D d, F f);
+impl_zip!(A a, B b, C c, D d, F f, G g);
+impl_zip!(A a, B b, C c, D d, F f, G g, H h);
+impl_zip!(A a, B b, C c, D d, F f, G g, H h, I i);
+impl_zip!(A a, B b, C c, D d, F f, G g, H h, I i, J j);
+impl_zip!(A a, B b, C c, D d, F f, G g, H h, I i, J j, K k);
+impl_zip!(A a, B b, C c, D d, F f, G g, H h, I i, J j, K k, L l);
+impl_zip!(A a, B b, C c, D d, F f, G g, H h, I i, J j, K k, L l, M m);
@@ -0,0 +1,107 @@
+#[macro_use]
+extern crate carrier;
+
+use std::cell::Cell;
+use std::num::ParseIntError;
+
+use carrier::{Alternative, NoneError, Validated};
+
+
+#[derive(Debug, PartialEq)]
+enum LoadError {
+    BadNumber,
+    Missing,
+}
+
+impl From<ParseIntError> for LoadError {
+    fn from(_err: ParseIntError) -> LoadError { LoadError::BadNumber }
+}
+
+impl From<NoneError> for LoadError {
+    fn from(_err: NoneError) -> LoadError { LoadError::Missing }
+}
+
+#[test]
+fn test_alt() {
+    let calls = Cell::new(0);
+    let fallback = || {
+        calls.set(calls.get() + 1);
+        Some(2)
+    };
+    assert_eq!(Some(1).alt(fallback), Some(1));
+    assert_eq!(calls.get(), 0);
+    assert_eq!(None.alt(fallback), Some(2));
+    assert_eq!(calls.get(), 1);
+
+    let rv: Result<i32, ParseIntError> = "x".parse::<i32>().alt(|| "42".parse());
+    assert_eq!(rv, Ok(42));
+}
+
+#[test]
+fn test_first_ok_first_value_is_lazy() {
+    fn load(calls: &Cell<i32>) -> Result<i32, LoadError> {
+        Ok(first_ok!(Some(1), {
+            calls.set(calls.get() + 1);
+            "2".parse::<i32>()
+        }))
+    }
+    let calls = Cell::new(0);
+    assert_eq!(load(&calls), Ok(1));
+    assert_eq!(calls.get(), 0);
+}
+
+#[test]
+fn test_first_ok_mixed() {
+    fn load(cache: Option<i32>, disk: &str) -> Result<i32, LoadError> {
+        Ok(first_ok!(cache, disk.parse::<i32>()))
+    }
+    assert_eq!(load(Some(1), "2"), Ok(1));
+    assert_eq!(load(None, "2"), Ok(2));
+    assert_eq!(load(None, "x"), Err(LoadError::BadNumber));
+}
+
+#[test]
+fn test_first_ok_last_residual() {
+    fn load(disk: &str, cache: Option<i32>) -> Result<i32, LoadError> {
+        Ok(first_ok!(disk.parse::<i32>(), cache))
+    }
+    assert_eq!(load("x", None), Err(LoadError::Missing));
+}
+
+#[test]
+fn test_first_ok_all() {
+    fn load(a: &str, b: &str) -> Result<i32, Vec<LoadError>> {
+        Ok(first_ok!(all: a.parse::<i32>(), b.parse::<i32>()))
+    }
+    assert_eq!(load("x", "2"), Ok(2));
+    assert_eq!(load("x", "y"), Err(vec![LoadError::BadNumber, LoadError::BadNumber]));
+}
+
+#[test]
+fn test_first_ok_all_validated() {
+    fn load(a: Option<i32>, b: Option<i32>) -> Validated<i32, LoadError> {
+        ok!(first_ok!(all: a, b, None))
+    }
+    assert_eq!(load(None, Some(2)), Validated::Valid(2));
+    assert_eq!(load(None, None).errors().map(|x| x.len()), Some(3));
+}
+
+#[test]
+fn test_first_ok_in_try_block() {
+    let load = |cache: Option<i32>, disk: &str| -> (Result<i32, LoadError>, bool) {
+        let rv = try_block! {
+            first_ok!(cache, disk.parse::<i32>())
+        };
+        (rv, true)
+    };
+    assert_eq!(load(None, "42"), (Ok(42), true));
+    assert_eq!(load(None, "x"), (Err(LoadError::BadNumber), true));
+}
+
+#[test]
+fn test_first_ok_all_in_try_block() {
+    let rv: Result<i32, Vec<ParseIntError>> = try_block! {
+        first_ok!(all: "x".parse::<i32>(), "y".parse::<i32>())
+    };
+    assert_eq!(rv.unwrap_err().len(), 2);
+}
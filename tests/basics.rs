@@ -130,6 +130,7 @@ fn test_result_raw_ptr() {
 }
 
 #[test]
+#[allow(clippy::zero_ptr)]
 fn test_result_raw_ptr_mut() {
     fn foo() -> *mut i64 {
         0 as *mut i64
@@ -142,3 +143,12 @@ fn test_result_raw_ptr_mut() {
     }
     assert_eq!(bar(), None);
 }
+
+#[test]
+fn test_labeled() {
+    fn parse(s: &str) -> Result<i32, std::num::ParseIntError> {
+        Ok(try!(#"parse" s.parse::<i32>()))
+    }
+    assert_eq!(parse("42"), Ok(42));
+    assert!(parse("x").is_err());
+}
@@ -0,0 +1,104 @@
+#[macro_use]
+extern crate carrier;
+
+use std::convert::Infallible;
+use std::task::Poll;
+
+use carrier::{Branch, Completion, FromResidual};
+
+
+#[derive(PartialEq, Debug)]
+struct Error(i32);
+
+struct Response {
+    status: i32,
+}
+
+impl Branch for Response {
+    type Value = Response;
+    type Residual = Result<Infallible, Error>;
+
+    fn branch(self) -> Completion<Response, Result<Infallible, Error>> {
+        if self.status == 200 {
+            Completion::Value(self)
+        } else {
+            Completion::Abrupt(Err(Error(self.status)))
+        }
+    }
+}
+
+#[test]
+fn test_branch_builtin() {
+    assert_eq!(Ok::<i32, ()>(42).branch(), Completion::Value(42));
+    assert_eq!(Err::<i32, ()>(()).branch(), Completion::Abrupt(Err(())));
+    assert_eq!(Some(42).branch(), Completion::Value(42));
+    assert_eq!(None::<i32>.branch(), Completion::Abrupt(None));
+}
+
+#[test]
+fn test_custom_branch_into_result() {
+    fn http_test(status: i32) -> Result<i32, Error> {
+        let resp = try!(Response { status });
+        Ok(resp.status)
+    }
+    assert_eq!(http_test(404), Err(Error(404)));
+    assert_eq!(http_test(200), Ok(200));
+}
+
+#[test]
+fn test_custom_branch_into_option_result() {
+    fn next(status: i32) -> Option<Result<i32, Error>> {
+        let resp = try!(Response { status });
+        Some(Ok(resp.status))
+    }
+    assert_eq!(next(500), Some(Err(Error(500))));
+    assert_eq!(next(200), Some(Ok(200)));
+}
+
+#[test]
+fn test_custom_branch_into_poll() {
+    fn poll(status: i32) -> Poll<Result<i32, Error>> {
+        let resp = try!(Response { status });
+        ok!(resp.status)
+    }
+    assert_eq!(poll(500), Poll::Ready(Err(Error(500))));
+    assert_eq!(poll(200), Poll::Ready(Ok(200)));
+}
+
+#[test]
+fn test_completion_branch() {
+    fn half(x: i32) -> Completion<i32, &'static str> {
+        if x % 2 == 0 { Completion::Value(x / 2) } else { Completion::Abrupt("odd") }
+    }
+    fn quarter(x: i32) -> Completion<i32, String> {
+        let x = try!(half(x));
+        Completion::Value(try!(half(x)))
+    }
+    assert_eq!(quarter(168), Completion::Value(42));
+    assert_eq!(quarter(6), Completion::Abrupt("odd".to_string()));
+}
+
+#[test]
+fn test_custom_from_residual() {
+    #[derive(PartialEq, Debug)]
+    enum Status {
+        Done(i32),
+        Failed(String),
+    }
+
+    impl<E: ToString> FromResidual<Result<Infallible, E>> for Status {
+        fn from_residual(residual: Result<Infallible, E>) -> Status {
+            match residual {
+                Ok(never) => match never {},
+                Err(err) => Status::Failed(err.to_string()),
+            }
+        }
+    }
+
+    fn parse(s: &str) -> Status {
+        Status::Done(try!(s.parse::<i32>()))
+    }
+
+    assert_eq!(parse("42"), Status::Done(42));
+    assert_eq!(parse("x"), Status::Failed("invalid digit found in string".into()));
+}
@@ -0,0 +1,77 @@
+extern crate carrier;
+
+mod common;
+
+use std::num::ParseIntError;
+use std::task::Poll;
+
+use carrier::Carry;
+
+use common::{Error, Response};
+
+
+impl From<ParseIntError> for Error {
+    fn from(_err: ParseIntError) -> Error { Error(0) }
+}
+
+#[test]
+fn test_carry_custom_carrier() {
+    fn http_test(status: &str) -> Result<i32, Error> {
+        let status = status.parse::<i32>()?;
+        let resp = Response { status }.carry::<Result<i32, Error>>()?;
+        Ok(resp.status)
+    }
+    assert_eq!(http_test("404"), Err(Error(404)));
+    assert_eq!(http_test("200"), Ok(200));
+    assert_eq!(http_test("x"), Err(Error(0)));
+}
+
+#[test]
+fn test_carry_converts_like_try() {
+    fn parse(s: &str) -> Result<i32, Error> {
+        let x = s.parse::<i32>().carry::<Result<i32, Error>>()?;
+        Ok(x * 2)
+    }
+    assert_eq!(parse("21"), Ok(42));
+    assert_eq!(parse("x"), Err(Error(0)));
+}
+
+#[test]
+fn test_carry_option() {
+    fn first_char(s: &str) -> Option<char> {
+        let c = s.chars().next().carry::<Option<char>>()?;
+        Some(c.to_ascii_uppercase())
+    }
+    assert_eq!(first_char("abc"), Some('A'));
+    assert_eq!(first_char(""), None);
+}
+
+#[test]
+fn test_carry_iterator() {
+    fn next_field(fields: &mut std::slice::Iter<&str>) -> Option<Result<i32, ParseIntError>> {
+        let field = fields.next().carry::<Option<Result<i32, ParseIntError>>>()?;
+        Some(field.parse())
+    }
+    let fields = ["1", "x"];
+    let mut iter = fields.iter();
+    assert_eq!(next_field(&mut iter), Some(Ok(1)));
+    assert!(matches!(next_field(&mut iter), Some(Err(_))));
+    assert_eq!(next_field(&mut iter), None);
+}
+
+#[test]
+fn test_carry_poll() {
+    fn poll_parse(s: &str) -> Poll<Result<i32, Error>> {
+        let x = s.parse::<i32>().carry::<Poll<Result<i32, Error>>>()?;
+        Poll::Ready(Ok(x))
+    }
+    assert_eq!(poll_parse("42"), Poll::Ready(Ok(42)));
+    assert_eq!(poll_parse("x"), Poll::Ready(Err(Error(0))));
+
+    fn poll_next(s: &str) -> Poll<Option<Result<i32, Error>>> {
+        let x = s.parse::<i32>().carry::<Poll<Option<Result<i32, Error>>>>()?;
+        Poll::Ready(Some(Ok(x)))
+    }
+    assert_eq!(poll_next("42"), Poll::Ready(Some(Ok(42))));
+    assert_eq!(poll_next("x"), Poll::Ready(Some(Err(Error(0)))));
+}
@@ -0,0 +1,151 @@
+#[macro_use]
+extern crate carrier;
+
+mod common;
+
+use std::num::ParseIntError;
+
+use common::{Error, Response};
+
+
+fn parse(s: &str) -> Result<i32, ParseIntError> {
+    s.parse()
+}
+
+#[test]
+fn test_chain_option() {
+    fn half(x: i32) -> Option<i32> {
+        if x % 2 == 0 { Some(x / 2) } else { None }
+    }
+    let rv: Option<i32> = chain! {
+        x <- half(84);
+        y <- half(x);
+        pure x + y
+    };
+    assert_eq!(rv, Some(63));
+    let rv: Option<i32> = chain! {
+        x <- half(42);
+        y <- half(x);
+        pure x + y
+    };
+    assert_eq!(rv, None);
+}
+
+#[test]
+fn test_chain_let_annotated() {
+    let rv: Result<i64, ParseIntError> = chain! {
+        x <- parse("20");
+        let y: i64 = (x + 1).into();
+        let (a, b): (i64, i64) = (y, 21);
+        pure a + b
+    };
+    assert_eq!(rv, Ok(42));
+}
+
+#[test]
+fn test_chain_result() {
+    let rv: Result<i32, ParseIntError> = chain! {
+        x <- parse("20");
+        let y = x + 1;
+        z <- parse("21");
+        pure y + z
+    };
+    assert_eq!(rv, Ok(42));
+    let rv: Result<i32, ParseIntError> = chain! {
+        x <- parse("x");
+        pure x
+    };
+    assert!(rv.is_err());
+}
+
+#[test]
+fn test_chain_option_result() {
+    let rv: Option<Result<i32, ParseIntError>> = chain! {
+        x <- parse("42");
+        pure x
+    };
+    assert_eq!(rv, Some(Ok(42)));
+    let rv: Option<Result<i32, ParseIntError>> = chain! {
+        x <- parse("x");
+        pure x
+    };
+    assert!(matches!(rv, Some(Err(_))));
+}
+
+#[test]
+fn test_chain_does_not_return() {
+    struct Parsed {
+        value: Option<i32>,
+        fallback: i32,
+    }
+    fn build(s: &str) -> Parsed {
+        Parsed {
+            value: chain! {
+                x <- s.parse::<i32>().ok();
+                pure x * 2
+            },
+            fallback: 0,
+        }
+    }
+    assert_eq!(build("21").value, Some(42));
+    assert_eq!(build("x").value, None);
+    assert_eq!(build("x").fallback, 0);
+}
+
+#[test]
+fn test_chain_statements_and_tail() {
+    let rv: Result<i32, ParseIntError> = chain! {
+        parse("1");
+        parse("42")
+    };
+    assert_eq!(rv, Ok(42));
+    let rv: Result<i32, ParseIntError> = chain! {
+        parse("x");
+        parse("42")
+    };
+    assert!(rv.is_err());
+}
+
+#[test]
+fn test_chain_custom_carrier() {
+    fn fetch(status: i32) -> Response {
+        Response { status }
+    }
+
+    let rv: Result<i32, Error> = chain! {
+        a <- fetch(200);
+        b <- fetch(200);
+        pure a.status + b.status
+    };
+    assert_eq!(rv, Ok(400));
+    let rv: Result<i32, Error> = chain! {
+        a <- fetch(200);
+        b <- fetch(404);
+        pure a.status + b.status
+    };
+    assert_eq!(rv, Err(Error(404)));
+}
+
+#[test]
+fn test_chain_first_ok() {
+    let load = |cache: Option<i32>, disk: &str| -> (Result<i32, ParseIntError>, bool) {
+        let rv = chain! {
+            let x = first_ok!(cache, disk.parse::<i32>());
+            y <- parse("1");
+            pure x + y
+        };
+        (rv, true)
+    };
+    assert_eq!(load(Some(41), "x"), (Ok(42), true));
+    assert_eq!(load(None, "41"), (Ok(42), true));
+    assert!(matches!(load(None, "x"), (Err(_), true)));
+}
+
+#[test]
+fn test_chain_try() {
+    let rv: Option<i32> = chain! {
+        let x = try!("x".parse::<i32>().ok());
+        pure x
+    };
+    assert_eq!(rv, None);
+}
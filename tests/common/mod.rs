@@ -0,0 +1,23 @@
+use carrier::{Completion, IntoCompletion};
+
+
+#[derive(PartialEq, Debug)]
+pub struct Error(pub i32);
+
+pub struct Response {
+    pub status: i32,
+}
+
+impl<E, T> IntoCompletion<Result<T, E>> for Response
+    where E: From<Error>
+{
+    type Value = Response;
+
+    fn into_completion(self) -> Completion<Response, Result<T, E>> {
+        if self.status == 200 {
+            Completion::Value(self)
+        } else {
+            Completion::Abrupt(Err(Error(self.status).into()))
+        }
+    }
+}
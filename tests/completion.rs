@@ -0,0 +1,149 @@
+extern crate carrier;
+
+use std::convert::Infallible;
+
+use carrier::Completion;
+
+
+#[test]
+fn test_is_value_is_abrupt() {
+    let v: Completion<i32, ()> = Completion::Value(42);
+    let a: Completion<i32, ()> = Completion::Abrupt(());
+    assert!(v.is_value());
+    assert!(!v.is_abrupt());
+    assert!(!a.is_value());
+    assert!(a.is_abrupt());
+}
+
+#[test]
+fn test_value_abrupt() {
+    fn v() -> Completion<i32, &'static str> { Completion::Value(42) }
+    fn a() -> Completion<i32, &'static str> { Completion::Abrupt("fail") }
+    assert_eq!(v().value(), Some(42));
+    assert_eq!(v().abrupt(), None);
+    assert_eq!(a().value(), None);
+    assert_eq!(a().abrupt(), Some("fail"));
+}
+
+#[test]
+fn test_map() {
+    let v: Completion<i32, &str> = Completion::Value(21);
+    let a: Completion<i32, &str> = Completion::Abrupt("fail");
+    assert_eq!(v.map(|x| x * 2).value(), Some(42));
+    assert_eq!(a.map(|x| x * 2).abrupt(), Some("fail"));
+}
+
+#[test]
+fn test_map_abrupt() {
+    let v: Completion<i32, &str> = Completion::Value(42);
+    let a: Completion<i32, &str> = Completion::Abrupt("fail");
+    assert_eq!(v.map_abrupt(|x| x.len()).value(), Some(42));
+    assert_eq!(a.map_abrupt(|x| x.len()).abrupt(), Some(4));
+}
+
+#[test]
+fn test_and_then() {
+    fn half(x: i32) -> Completion<i32, &'static str> {
+        if x % 2 == 0 {
+            Completion::Value(x / 2)
+        } else {
+            Completion::Abrupt("odd")
+        }
+    }
+    let v: Completion<i32, &str> = Completion::Value(84);
+    let a: Completion<i32, &str> = Completion::Abrupt("fail");
+    assert_eq!(v.and_then(half).value(), Some(42));
+    assert_eq!(Completion::Value(43).and_then(half).abrupt(), Some("odd"));
+    assert_eq!(a.and_then(half).abrupt(), Some("fail"));
+}
+
+#[test]
+fn test_or_else() {
+    fn recover(x: &'static str) -> Completion<i32, usize> {
+        if x == "soft" {
+            Completion::Value(0)
+        } else {
+            Completion::Abrupt(x.len())
+        }
+    }
+    let v: Completion<i32, &str> = Completion::Value(42);
+    assert_eq!(v.or_else(recover).value(), Some(42));
+    assert_eq!(Completion::Abrupt("soft").or_else(recover).value(), Some(0));
+    assert_eq!(Completion::Abrupt("hard").or_else(recover).abrupt(), Some(4));
+}
+
+#[test]
+fn test_unwrap_value() {
+    let v: Completion<i32, ()> = Completion::Value(42);
+    assert_eq!(v.unwrap_value(), 42);
+}
+
+#[test]
+#[should_panic(expected = "called `Completion::unwrap_value()` on an `Abrupt` value: \"fail\"")]
+fn test_unwrap_value_abrupt() {
+    let a: Completion<i32, &str> = Completion::Abrupt("fail");
+    a.unwrap_value();
+}
+
+#[test]
+fn test_expect_value() {
+    let v: Completion<i32, ()> = Completion::Value(42);
+    assert_eq!(v.expect_value("no value"), 42);
+}
+
+#[test]
+#[should_panic(expected = "no value: \"fail\"")]
+fn test_expect_value_abrupt() {
+    let a: Completion<i32, &str> = Completion::Abrupt("fail");
+    a.expect_value("no value");
+}
+
+#[test]
+fn test_as_ref() {
+    let v: Completion<String, String> = Completion::Value("42".to_string());
+    assert_eq!(v.as_ref().map(|x| x.len()).value(), Some(2));
+    assert_eq!(v.value(), Some("42".to_string()));
+}
+
+#[test]
+fn test_as_mut() {
+    let mut v: Completion<i32, i32> = Completion::Value(21);
+    if let Completion::Value(x) = v.as_mut() {
+        *x *= 2;
+    }
+    assert_eq!(v.value(), Some(42));
+
+    let mut a: Completion<i32, i32> = Completion::Abrupt(1);
+    if let Completion::Abrupt(x) = a.as_mut() {
+        *x += 1;
+    }
+    assert_eq!(a.abrupt(), Some(2));
+}
+
+#[test]
+fn test_transpose() {
+    let v: Completion<Option<i32>, ()> = Completion::Value(Some(42));
+    let n: Completion<Option<i32>, ()> = Completion::Value(None);
+    let a: Completion<Option<i32>, ()> = Completion::Abrupt(());
+    assert_eq!(v.transpose().and_then(|x| x.value()), Some(42));
+    assert!(n.transpose().is_none());
+    assert_eq!(a.transpose().map(|x| x.is_abrupt()), Some(true));
+}
+
+#[test]
+fn test_flatten() {
+    let v: Completion<Completion<i32, ()>, ()> =
+        Completion::Value(Completion::Value(42));
+    let i: Completion<Completion<i32, ()>, ()> =
+        Completion::Value(Completion::Abrupt(()));
+    let a: Completion<Completion<i32, ()>, ()> = Completion::Abrupt(());
+    assert_eq!(v.flatten().value(), Some(42));
+    assert!(i.flatten().is_abrupt());
+    assert!(a.flatten().is_abrupt());
+}
+
+#[test]
+fn test_into_value() {
+    let v: Completion<i32, Infallible> = Completion::Value(42);
+    assert_eq!(v.into_value(), 42);
+}
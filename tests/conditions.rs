@@ -0,0 +1,146 @@
+#![cfg(feature = "conditions")]
+#[macro_use]
+extern crate carrier;
+
+use std::thread;
+
+use carrier::condition::{self, Condition, Restart};
+
+
+#[derive(Debug, PartialEq)]
+struct Malformed(String);
+
+impl Condition for Malformed {}
+
+#[derive(Debug, PartialEq)]
+enum AppError {
+    Malformed(Malformed),
+}
+
+impl From<Malformed> for AppError {
+    fn from(err: Malformed) -> AppError {
+        AppError::Malformed(err)
+    }
+}
+
+fn parse_field(raw: &str) -> Result<i32, Malformed> {
+    raw.parse::<i32>().map_err(|_| Malformed(raw.to_string()))
+}
+
+fn sum_fields(raw: &str) -> Result<i32, AppError> {
+    let mut rv = 0;
+    for field in raw.split(',') {
+        rv += try!(?cond parse_field(field));
+    }
+    Ok(rv)
+}
+
+#[test]
+fn test_propagates_without_handler() {
+    assert_eq!(sum_fields("1,x,3"), Err(AppError::Malformed(Malformed("x".into()))));
+}
+
+#[test]
+fn test_substitute_value() {
+    let guard = condition::handle(|_: &Malformed| Restart::Use(0));
+    assert_eq!(sum_fields("1,x,3"), Ok(4));
+    drop(guard);
+    assert!(sum_fields("1,x,3").is_err());
+}
+
+#[test]
+fn test_handler_sees_error() {
+    let _guard = condition::handle(|err: &Malformed| {
+        if err.0 == "ten" {
+            Restart::Use(10)
+        } else {
+            Restart::Propagate
+        }
+    });
+    assert_eq!(sum_fields("1,ten"), Ok(11));
+    assert_eq!(sum_fields("1,x"), Err(AppError::Malformed(Malformed("x".into()))));
+}
+
+#[test]
+fn test_most_recent_handler_first() {
+    let _outer = condition::handle(|_: &Malformed| Restart::Use(100));
+    assert_eq!(sum_fields("x"), Ok(100));
+    {
+        let _inner = condition::handle(|err: &Malformed| {
+            if err.0 == "y" {
+                Restart::Use(1)
+            } else {
+                Restart::Propagate
+            }
+        });
+        assert_eq!(sum_fields("x,y"), Ok(101));
+    }
+    assert_eq!(sum_fields("y"), Ok(100));
+}
+
+#[test]
+fn test_value_type_must_match() {
+    let _guard = condition::handle(|_: &Malformed| Restart::Use("zero"));
+    assert!(sum_fields("x").is_err());
+}
+
+#[test]
+fn test_mismatched_numeric_handler_is_skipped() {
+    let _wide = condition::handle(|_: &Malformed| Restart::Use(0i64));
+    assert!(sum_fields("x").is_err());
+    let _exact = condition::handle(|_: &Malformed| Restart::Use(7i32));
+    let _wider = condition::handle(|_: &Malformed| Restart::Use(1u32));
+    assert_eq!(sum_fields("x"), Ok(7));
+}
+
+#[test]
+fn test_handlers_are_thread_local() {
+    let _guard = condition::handle(|_: &Malformed| Restart::Use(0));
+    let rv = thread::spawn(|| sum_fields("x")).join().unwrap();
+    assert!(rv.is_err());
+    assert_eq!(sum_fields("x"), Ok(0));
+}
+
+#[test]
+fn test_plain_try_does_not_signal() {
+    fn sum(raw: &str) -> Result<i32, AppError> {
+        let mut rv = 0;
+        for field in raw.split(',') {
+            rv += try!(parse_field(field));
+        }
+        Ok(rv)
+    }
+
+    let _guard = condition::handle(|_: &Malformed| Restart::Use(0));
+    assert_eq!(sum_fields("1,x"), Ok(1));
+    assert!(sum("1,x").is_err());
+}
+
+#[test]
+fn test_labeled_condition() {
+    fn parse(raw: &str) -> Result<i32, AppError> {
+        Ok(try!(#"field" ?cond parse_field(raw)))
+    }
+
+    let _guard = condition::handle(|_: &Malformed| Restart::Use(7));
+    assert_eq!(parse("x"), Ok(7));
+}
+
+#[test]
+fn test_inferred_expression_type() {
+    fn parse(raw: &str) -> Result<i32, std::num::ParseIntError> {
+        let value = try!(raw.parse());
+        Ok(value)
+    }
+    assert_eq!(parse("42"), Ok(42));
+}
+
+#[test]
+fn test_other_errors_propagate() {
+    fn parse(raw: &str) -> Result<i32, std::num::ParseIntError> {
+        Ok(try!(raw.parse::<i32>()))
+    }
+
+    let _guard = condition::handle(|_: &Malformed| Restart::Use(0));
+    assert!(parse("x").is_err());
+}
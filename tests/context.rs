@@ -0,0 +1,110 @@
+#[macro_use]
+extern crate carrier;
+
+use std::error::Error;
+use std::fmt;
+
+use carrier::{Context, NoneError};
+
+
+#[derive(Debug, PartialEq)]
+struct IoError(&'static str);
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "io error: {}", self.0)
+    }
+}
+
+impl Error for IoError {}
+
+fn open(path: &str) -> Result<String, IoError> {
+    if path == "config.toml" {
+        Ok("answer = 42".to_string())
+    } else {
+        Err(IoError("not found"))
+    }
+}
+
+fn read_config(path: &str) -> Result<String, Context<IoError>> {
+    Ok(try!(open(path); "opening {}", path))
+}
+
+fn load_config(path: &str) -> Result<String, Context<IoError>> {
+    Ok(try!(read_config(path); "loading config"))
+}
+
+fn chain(err: &dyn Error) -> Vec<String> {
+    let mut rv = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(err) = source {
+        rv.push(err.to_string());
+        source = err.source();
+    }
+    rv
+}
+
+#[test]
+fn test_context_value() {
+    assert_eq!(load_config("config.toml").unwrap(), "answer = 42");
+}
+
+#[test]
+fn test_context_message() {
+    let err = read_config("missing.toml").unwrap_err();
+    assert_eq!(err.to_string(), "opening missing.toml");
+    assert_eq!(err.message(), "opening missing.toml");
+    assert_eq!(err.error(), &IoError("not found"));
+}
+
+#[test]
+fn test_context_stacks() {
+    let err = load_config("missing.toml").unwrap_err();
+    assert_eq!(err.messages().collect::<Vec<_>>(),
+               vec!["loading config", "opening missing.toml"]);
+    assert_eq!(chain(&err), vec![
+        "loading config",
+        "opening missing.toml",
+        "io error: not found",
+    ]);
+    assert_eq!(err.into_error(), IoError("not found"));
+}
+
+#[test]
+fn test_context_into_boxed_error() {
+    fn run() -> Result<(), Box<dyn Error>> {
+        try!(load_config("missing.toml"); "starting up");
+        Ok(())
+    }
+    let err = run().unwrap_err();
+    assert_eq!(chain(&*err), vec![
+        "starting up",
+        "loading config",
+        "opening missing.toml",
+        "io error: not found",
+    ]);
+}
+
+#[test]
+fn test_context_option() {
+    fn first(items: &[i32]) -> Result<i32, Context<NoneError>> {
+        Ok(*try!(items.first(); "no items"))
+    }
+    assert_eq!(first(&[42]).unwrap(), 42);
+    let err = first(&[]).unwrap_err();
+    assert_eq!(chain(&err), vec!["no items", "expected a value but found none"]);
+}
+
+#[test]
+fn test_context_wrap() {
+    let ctx = Context::new(IoError("x"), "inner").wrap("middle").wrap("outer");
+    assert_eq!(ctx.messages().collect::<Vec<_>>(), vec!["outer", "middle", "inner"]);
+}
+
+#[test]
+fn test_context_flatten() {
+    let nested = Context::new(Context::new(IoError("x"), "inner").wrap("middle"), "outer");
+    let flat: Context<IoError> = nested.into();
+    assert_eq!(flat.messages().collect::<Vec<_>>(), vec!["outer", "middle", "inner"]);
+    assert_eq!(flat.error(), &IoError("x"));
+}
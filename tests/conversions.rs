@@ -0,0 +1,69 @@
+#[macro_use]
+extern crate carrier;
+
+use std::ops::ControlFlow;
+
+use carrier::{Completion, IntoCompletion};
+
+
+#[test]
+fn test_result_round_trip() {
+    let ok: Result<i32, &str> = Ok(42);
+    let err: Result<i32, &str> = Err("fail");
+    assert_eq!(Completion::from(ok), Completion::Value(42));
+    assert_eq!(Completion::from_result(err), Completion::Abrupt("fail"));
+    assert_eq!(Result::from(Completion::from(ok)), ok);
+    assert_eq!(Completion::from(err).into_result(), err);
+}
+
+#[test]
+fn test_control_flow_round_trip() {
+    let cont: ControlFlow<&str, i32> = ControlFlow::Continue(42);
+    let brk: ControlFlow<&str, i32> = ControlFlow::Break("stop");
+    assert_eq!(Completion::from(cont), Completion::Value(42));
+    assert_eq!(Completion::from_control_flow(brk), Completion::Abrupt("stop"));
+    assert_eq!(ControlFlow::from(Completion::from(cont)), cont);
+    assert_eq!(Completion::from(brk).into_control_flow(), brk);
+}
+
+#[test]
+fn test_option_round_trip() {
+    assert_eq!(Completion::from(Some(42)), Completion::Value(42));
+    assert_eq!(Completion::<i32, ()>::from_option(None), Completion::Abrupt(()));
+    assert_eq!(Option::from(Completion::from(Some(42))), Some(42));
+    assert_eq!(Option::<i32>::from(Completion::Abrupt(())), None);
+}
+
+#[test]
+fn test_http_example_with_conversions() {
+    struct Response {
+        status: i32,
+    }
+
+    #[derive(PartialEq, Debug)]
+    struct Error(i32);
+
+    impl Response {
+        fn check(self) -> Result<Response, Error> {
+            if self.status == 200 { Ok(self) } else { Err(Error(self.status)) }
+        }
+    }
+
+    impl<E, T> IntoCompletion<Result<T, E>> for Response
+        where E: From<Error>
+    {
+        type Value = Response;
+
+        fn into_completion(self) -> Completion<Response, Result<T, E>> {
+            Completion::from(self.check()).map_abrupt(|err| Err(err.into()))
+        }
+    }
+
+    fn http_test(status: i32) -> Result<(), Error> {
+        let _resp = try!(Response { status });
+        Ok(())
+    }
+
+    assert_eq!(http_test(404), Err(Error(404)));
+    assert_eq!(http_test(200), Ok(()));
+}
@@ -5,6 +5,7 @@ use carrier::{IntoCompletion, Completion};
 
 
 #[test]
+#[allow(clippy::redundant_field_names)]
 fn test_http_example() {
     struct Response {
         status: i32,
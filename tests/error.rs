@@ -0,0 +1,103 @@
+#[macro_use]
+extern crate carrier;
+
+use std::backtrace::BacktraceStatus;
+use std::error::Error as StdError;
+use std::fmt;
+use std::num::ParseIntError;
+
+use carrier::{Context, Error, NoneError};
+
+
+#[derive(Debug, PartialEq)]
+struct Outer(ParseIntError);
+
+impl fmt::Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "outer failed")
+    }
+}
+
+impl StdError for Outer {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn parse(s: &str) -> Result<i32, Error> {
+    Ok(try!(s.parse::<i32>()))
+}
+
+#[test]
+fn test_error_from_try() {
+    assert_eq!(parse("42").unwrap(), 42);
+    let err = parse("x").unwrap_err();
+    assert!(err.is::<ParseIntError>());
+    assert_eq!(err.to_string(), "invalid digit found in string");
+}
+
+#[test]
+fn test_error_from_option() {
+    fn first(items: &[i32]) -> Result<i32, Error> {
+        Ok(*try!(items.first()))
+    }
+    assert!(first(&[]).unwrap_err().is::<NoneError>());
+}
+
+#[test]
+fn test_error_downcast() {
+    let err = parse("x").unwrap_err();
+    assert!(err.downcast_ref::<NoneError>().is_none());
+    assert_eq!(err.downcast_ref::<ParseIntError>(), Some(&"x".parse::<i32>().unwrap_err()));
+    let err = match err.downcast::<NoneError>() {
+        Ok(_) => panic!("downcast to wrong type"),
+        Err(err) => err,
+    };
+    let err: ParseIntError = err.downcast().unwrap();
+    assert_eq!(err, "x".parse::<i32>().unwrap_err());
+}
+
+#[test]
+fn test_error_downcast_mut() {
+    let mut err = Error::new(Outer("x".parse::<i32>().unwrap_err()));
+    err.downcast_mut::<Outer>().unwrap().0 = "".parse::<i32>().unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "cannot parse integer from empty string");
+}
+
+#[test]
+fn test_error_chain() {
+    let err = Error::from(Outer("x".parse::<i32>().unwrap_err()));
+    let messages: Vec<_> = err.chain().map(|x| x.to_string()).collect();
+    assert_eq!(messages, vec!["outer failed", "invalid digit found in string"]);
+    assert_eq!(err.root_cause().to_string(), "invalid digit found in string");
+    assert!(err.source().is_some());
+}
+
+#[test]
+fn test_error_context() {
+    fn load(s: &str) -> Result<i32, Error> {
+        Ok(try!(s.parse::<i32>(); "loading {}", s))
+    }
+    let err = load("x").unwrap_err();
+    assert!(err.is::<Context<ParseIntError>>());
+    let messages: Vec<_> = err.chain().map(|x| x.to_string()).collect();
+    assert_eq!(messages, vec!["loading x", "invalid digit found in string"]);
+}
+
+#[test]
+fn test_error_backtrace() {
+    let err = parse("x").unwrap_err();
+    match err.backtrace().status() {
+        BacktraceStatus::Captured | BacktraceStatus::Disabled => {}
+        status => panic!("unexpected backtrace status {:?}", status),
+    }
+}
+
+#[test]
+fn test_error_into_boxed() {
+    fn run() -> Result<(), Box<dyn StdError + Send + Sync>> {
+        try!(parse("x"));
+        Ok(())
+    }
+    assert_eq!(run().unwrap_err().to_string(), "invalid digit found in string");
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "fault-injection")]
+#[macro_use]
+extern crate carrier;
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+use std::thread;
+
+use carrier::{fault, Completion, NoneError};
+
+
+#[derive(Debug, PartialEq)]
+struct DiskError(&'static str);
+
+impl fmt::Display for DiskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "disk error: {}", self.0)
+    }
+}
+
+impl Error for DiskError {}
+
+#[derive(Debug)]
+enum AppError {
+    Parse(ParseIntError),
+    Io(io::Error),
+}
+
+impl From<ParseIntError> for AppError {
+    fn from(err: ParseIntError) -> AppError { AppError::Parse(err) }
+}
+
+impl From<io::Error> for AppError {
+    fn from(err: io::Error) -> AppError { AppError::Io(err) }
+}
+
+fn parse(s: &str) -> Result<i32, AppError> {
+    Ok(try!(#"parse" s.parse::<i32>()))
+}
+
+const PARSE_LOCATED_LINE: u32 = line!() + 2;
+fn parse_located(s: &str) -> Result<i32, AppError> {
+    Ok(try!(#"located" s.parse::<i32>()))
+}
+
+const PARSE_UNLABELED_LINE: u32 = line!() + 2;
+fn parse_unlabeled(s: &str) -> Result<i32, AppError> {
+    Ok(try!(s.parse::<i32>()))
+}
+
+fn bad_digit() -> ParseIntError {
+    "x".parse::<i32>().unwrap_err()
+}
+
+#[test]
+fn test_inject_by_label() {
+    let guard = fault::inject(fault::label("parse"), |_| bad_digit());
+    match parse("42") {
+        Err(AppError::Parse(err)) => assert_eq!(err, bad_digit()),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(guard.hits(), 1);
+    drop(guard);
+    assert_eq!(parse("42").unwrap(), 42);
+}
+
+#[test]
+fn test_inject_by_location() {
+    let guard = fault::inject(fault::at(file!(), PARSE_LOCATED_LINE), |_| bad_digit());
+    assert!(matches!(parse_located("42"), Err(AppError::Parse(_))));
+    assert_eq!(parse("42").unwrap(), 42);
+    assert_eq!(guard.hits(), 1);
+}
+
+#[test]
+fn test_unlabeled_is_not_injected() {
+    let guard = fault::inject(fault::at(file!(), PARSE_UNLABELED_LINE), |_| bad_digit());
+    assert_eq!(parse_unlabeled("42").unwrap(), 42);
+    assert_eq!(guard.hits(), 0);
+}
+
+#[test]
+fn test_unlabeled_non_static_errors() {
+    fn forward<E>(rv: Result<i32, E>) -> Result<i32, E> {
+        Ok(try!(rv) + 1)
+    }
+    fn borrowed(err: &str) -> Result<i32, &str> {
+        Ok(try!(Err(err)))
+    }
+    assert_eq!(forward::<()>(Ok(41)), Ok(42));
+    assert_eq!(borrowed(&String::from("x")), Err("x"));
+}
+
+#[test]
+fn test_temporaries_outlive_the_expression() {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    fn lookup(map: &RefCell<HashMap<i32, i32>>) -> Option<i32> {
+        Some(*try!(map.borrow().get(&1)) + 1)
+    }
+    fn lookup_labeled(map: &RefCell<HashMap<i32, i32>>) -> Option<i32> {
+        Some(*try!(#"lookup" map.borrow().get(&1)) + 1)
+    }
+    let map = RefCell::new(HashMap::new());
+    map.borrow_mut().insert(1, 41);
+    assert_eq!(lookup(&map), Some(42));
+    assert_eq!(lookup_labeled(&map), Some(42));
+}
+
+#[test]
+fn test_inject_io_error() {
+    fn read() -> Result<String, io::Error> {
+        Ok("data".into())
+    }
+    const LOAD_LINE: u32 = line!() + 2;
+    fn load() -> Result<String, AppError> {
+        Ok(try!(#"read" read()))
+    }
+    let _guard = fault::inject(fault::label("read"), |site| {
+        io::Error::other(format!("injected at {}", site.line()))
+    });
+    match load() {
+        Err(AppError::Io(err)) => {
+            assert_eq!(err.to_string(), format!("injected at {}", LOAD_LINE));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_inject_evaluates_expression() {
+    fn count(calls: &mut i32) -> Result<(), AppError> {
+        try!(#"count" {
+            *calls += 1;
+            Ok::<_, ParseIntError>(())
+        });
+        Ok(())
+    }
+    let mut calls = 0;
+    let _guard = fault::inject(fault::label("count"), |_| bad_digit());
+    assert!(count(&mut calls).is_err());
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn test_inject_boxed_error() {
+    fn open() -> Result<(), Box<dyn Error + Send + Sync>> {
+        try!(#"open" Ok::<_, DiskError>(()));
+        Ok(())
+    }
+    let _guard = fault::inject(fault::label("open"), |_| DiskError("boom"));
+    let err = open().unwrap_err();
+    assert_eq!(err.to_string(), "disk error: boom");
+}
+
+#[test]
+fn test_inject_closure() {
+    let parse = |s: &str| {
+        let x = try!(#"closure" s.parse::<i32>());
+        Ok::<_, ParseIntError>(x)
+    };
+    let _guard = fault::inject(fault::label("closure"), |_| bad_digit());
+    assert_eq!(parse("42"), Err(bad_digit()));
+}
+
+#[test]
+fn test_inject_option() {
+    fn first(s: &str) -> Option<char> {
+        Some(try!(#"first" s.chars().next()))
+    }
+    let guard = fault::inject(fault::label("first"), |_| NoneError);
+    assert_eq!(first("x"), None);
+    assert_eq!(guard.hits(), 1);
+}
+
+#[test]
+fn test_inject_is_thread_local() {
+    let _guard = fault::inject(fault::label("parse"), |_| bad_digit());
+    assert!(parse("42").is_err());
+    let rv = thread::spawn(|| parse("42").unwrap()).join().unwrap();
+    assert_eq!(rv, 42);
+}
+
+#[test]
+#[should_panic(expected = "cannot inject a fault of type `fault::DiskError`")]
+fn test_inject_mismatched_error() {
+    let _guard = fault::inject(fault::label("parse"), |_| DiskError("boom"));
+    let _ = parse("42");
+}
+
+#[test]
+#[should_panic(expected = "cannot inject a fault")]
+fn test_inject_unsupported_expression() {
+    fn half(x: i32) -> Completion<i32, &'static str> {
+        Completion::Value(try!(#"half" Completion::Value::<i32, &'static str>(x / 2)))
+    }
+    let _guard = fault::inject(fault::label("half"), |_| "odd");
+    let _ = half(42);
+}
+
+#[test]
+fn test_label_with_map_form() {
+    fn parse(s: &str) -> Result<i32, AppError> {
+        Ok(try!(#"mapped" s.parse::<i32>() => AppError::Parse))
+    }
+    let _guard = fault::inject(fault::label("mapped"), |_| bad_digit());
+    assert!(matches!(parse("1"), Err(AppError::Parse(_))));
+}
@@ -0,0 +1,119 @@
+#[macro_use]
+extern crate carrier;
+
+use std::cell::Cell;
+use std::num::ParseIntError;
+use std::panic;
+
+use carrier::Transaction;
+
+
+fn parse_guarded(cleaned: &Cell<usize>, raw: &str) -> Result<i32, ParseIntError> {
+    on_abrupt!(|| cleaned.set(cleaned.get() + 1), {
+        Ok(try!(raw.parse::<i32>()))
+    })
+}
+
+#[test]
+fn test_on_abrupt_try() {
+    let cleaned = Cell::new(0);
+    assert_eq!(parse_guarded(&cleaned, "42"), Ok(42));
+    assert_eq!(cleaned.get(), 0);
+    assert!(parse_guarded(&cleaned, "x").is_err());
+    assert_eq!(cleaned.get(), 1);
+}
+
+#[test]
+fn test_on_abrupt_panic() {
+    fn check(value: i32) -> i32 {
+        assert!(value >= 0, "failed midway");
+        value
+    }
+
+    let cleaned = Cell::new(0);
+    let rv = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        on_abrupt!(|| cleaned.set(1), {
+            check(-1)
+        })
+    }));
+    assert!(rv.is_err());
+    assert_eq!(cleaned.get(), 1);
+}
+
+#[test]
+fn test_on_abrupt_try_block() {
+    let cleaned = Cell::new(false);
+    let rv: Result<i32, ParseIntError> = try_block! {
+        on_abrupt!(|| cleaned.set(true), {
+            try!("x".parse::<i32>())
+        })
+    };
+    assert!(rv.is_err());
+    assert!(cleaned.get());
+}
+
+#[test]
+fn test_on_abrupt_returned_error_completes() {
+    let cleaned = Cell::new(false);
+    let rv: Result<i32, &str> = on_abrupt!(|| cleaned.set(true), {
+        Err("not early")
+    });
+    assert_eq!(rv, Err("not early"));
+    assert!(!cleaned.get());
+}
+
+#[test]
+fn test_defer_err() {
+    fn parse(cleaned: &Cell<usize>, raw: &str) -> Result<i32, ParseIntError> {
+        defer_err!(|| cleaned.set(cleaned.get() + 1), {
+            Ok(try!(raw.parse::<i32>()))
+        })
+    }
+
+    let cleaned = Cell::new(0);
+    assert_eq!(parse(&cleaned, "42"), Ok(42));
+    assert_eq!(cleaned.get(), 0);
+    assert!(parse(&cleaned, "x").is_err());
+    assert_eq!(cleaned.get(), 1);
+}
+
+#[test]
+fn test_on_value() {
+    fn parse(finished: &Cell<usize>, raw: &str) -> Result<i32, ParseIntError> {
+        on_value!(|| finished.set(finished.get() + 1), {
+            Ok(try!(raw.parse::<i32>()))
+        })
+    }
+
+    let finished = Cell::new(0);
+    assert!(parse(&finished, "x").is_err());
+    assert_eq!(finished.get(), 0);
+    assert_eq!(parse(&finished, "1"), Ok(1));
+    assert_eq!(finished.get(), 1);
+}
+
+#[test]
+fn test_transaction_commit() {
+    let rolled_back = Cell::new(false);
+    let tx = Transaction::new(|| rolled_back.set(true));
+    tx.commit();
+    assert!(!rolled_back.get());
+}
+
+#[test]
+fn test_transaction_rollback() {
+    fn transfer(balance: &Cell<i32>, amount: &str) -> Result<(), ParseIntError> {
+        balance.set(balance.get() - 10);
+        let tx = Transaction::new(|| balance.set(balance.get() + 10));
+        let amount = try!(amount.parse::<i32>());
+        balance.set(balance.get() + 10 - amount);
+        tx.commit();
+        Ok(())
+    }
+
+    let balance = Cell::new(100);
+    assert!(transfer(&balance, "x").is_err());
+    assert_eq!(balance.get(), 100);
+    assert!(transfer(&balance, "30").is_ok());
+    assert_eq!(balance.get(), 70);
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "hooks")]
+#[macro_use]
+extern crate carrier;
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::num::ParseIntError;
+
+use carrier::CallSite;
+
+
+thread_local! {
+    static EVENTS: RefCell<Vec<(u32, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(site: &'static CallSite, abrupt: &dyn Debug) {
+    EVENTS.with(|events| {
+        events.borrow_mut().push((site.line(), format!("{:?}", abrupt)));
+    });
+}
+
+fn take_events() -> Vec<(u32, String)> {
+    EVENTS.with(|events| events.borrow_mut().drain(..).collect())
+}
+
+const PARSE_LINE: u32 = line!() + 2;
+fn parse(s: &str) -> Result<i32, ParseIntError> {
+    Ok(try!(s.parse::<i32>()))
+}
+
+#[test]
+fn test_hook_called_on_abrupt() {
+    carrier::set_abrupt_hook(record);
+    take_events();
+    assert!(parse("x").is_err());
+    assert_eq!(take_events(), vec![
+        (PARSE_LINE, "Err(ParseIntError { kind: InvalidDigit })".to_string()),
+    ]);
+}
+
+#[test]
+fn test_hook_not_called_on_value() {
+    carrier::set_abrupt_hook(record);
+    take_events();
+    assert_eq!(parse("42"), Ok(42));
+    assert!(take_events().is_empty());
+}
+
+#[test]
+fn test_hook_reports_residual_before_conversion() {
+    #[derive(Debug)]
+    struct AppError;
+
+    impl From<ParseIntError> for AppError {
+        fn from(_: ParseIntError) -> AppError { AppError }
+    }
+
+    fn parse_app(s: &str) -> Result<i32, AppError> {
+        Ok(try!(s.parse::<i32>()))
+    }
+
+    fn first(s: &str) -> Option<char> {
+        Some(try!(s.chars().next()))
+    }
+
+    carrier::set_abrupt_hook(record);
+    take_events();
+    assert!(parse_app("x").is_err());
+    assert_eq!(first(""), None);
+    let events: Vec<_> = take_events().into_iter().map(|(_, abrupt)| abrupt).collect();
+    assert_eq!(events, vec!["Err(ParseIntError { kind: InvalidDigit })", "None"]);
+}
+
+#[test]
+fn test_hook_opaque_abrupt() {
+    struct NoDebug;
+
+    fn fail() -> Result<(), NoDebug> {
+        try!(Err::<i32, _>(NoDebug));
+        Ok(())
+    }
+
+    carrier::set_abrupt_hook(record);
+    take_events();
+    assert!(fail().is_err());
+    let events = take_events();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].1.starts_with("<core::result::Result<i32, "));
+}
+
+#[test]
+fn test_hook_registry() {
+    carrier::set_abrupt_hook(record);
+    assert!(carrier::hook::abrupt_hook().is_some());
+    assert!(carrier::set_abrupt_hook(record).is_some());
+}
@@ -0,0 +1,130 @@
+#[macro_use]
+extern crate carrier;
+
+mod common;
+
+use std::num::ParseIntError;
+
+use carrier::{Completion, NoneError};
+
+use common::{Error, Response};
+
+
+#[test]
+fn test_try_continue() {
+    let mut values = vec![];
+    for item in &["1", "x", "2"] {
+        values.push(try_continue!(item.parse::<i32>()));
+    }
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[test]
+fn test_try_continue_handler() {
+    let mut errors = vec![];
+    let mut values = vec![];
+    for item in &["1", "x", "2", "y"] {
+        values.push(try_continue!(item.parse::<i32>(), |err| {
+            errors.push(format!("{}: {}", item, err));
+        }));
+    }
+    assert_eq!(values, vec![1, 2]);
+    assert_eq!(errors, vec![
+        "x: invalid digit found in string",
+        "y: invalid digit found in string",
+    ]);
+}
+
+#[test]
+fn test_try_continue_label() {
+    let mut pairs = vec![];
+    'outer: for a in &[Some(1), None, Some(2)] {
+        for b in &[1, 2] {
+            let a = try_continue!('outer, *a);
+            pairs.push((a, *b));
+        }
+    }
+    assert_eq!(pairs, vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+}
+
+#[test]
+fn test_try_break() {
+    let mut values = vec![];
+    for item in &[Some(1), Some(2), None, Some(3)] {
+        values.push(try_break!(*item));
+    }
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[test]
+fn test_try_break_handler_and_label() {
+    let mut seen = vec![];
+    let mut failed = None;
+    'outer: loop {
+        for item in &["1", "2", "x"] {
+            seen.push(try_break!('outer, item.parse::<i32>(), |err: ParseIntError| {
+                failed = Some(err);
+            }));
+        }
+    }
+    assert_eq!(seen, vec![1, 2]);
+    assert!(failed.is_some());
+}
+
+#[test]
+fn test_try_continue_option_residual() {
+    let mut missing = 0;
+    for item in &[Some(1), None] {
+        try_continue!(*item, |_: NoneError| missing += 1);
+    }
+    assert_eq!(missing, 1);
+}
+
+#[test]
+fn test_try_continue_completion() {
+    let items: Vec<Completion<i32, &str>> = vec![
+        Completion::Value(1),
+        Completion::Abrupt("skip"),
+        Completion::Value(2),
+    ];
+    let mut skipped = vec![];
+    let mut sum = 0;
+    for item in items {
+        sum += try_continue!(item, |reason| skipped.push(reason));
+    }
+    assert_eq!(sum, 3);
+    assert_eq!(skipped, vec!["skip"]);
+}
+
+#[test]
+fn test_try_continue_custom_carrier() {
+    let mut failed = vec![];
+    let mut ok = vec![];
+    for status in &[200, 404, 200, 500] {
+        let resp = try_continue!(Response { status: *status }, |err: Error| failed.push(err));
+        ok.push(resp.status);
+    }
+    assert_eq!(ok, vec![200, 200]);
+    assert_eq!(failed, vec![Error(404), Error(500)]);
+}
+
+#[test]
+fn test_try_continue_handler_methods() {
+    let mut lengths = vec![];
+    for item in &["1", "xy"] {
+        try_continue!(item.parse::<i32>(), |err| lengths.push(err.to_string().len()));
+    }
+    assert_eq!(lengths, vec!["invalid digit found in string".len()]);
+}
+
+#[test]
+fn test_try_break_custom_carrier() {
+    let mut seen = 0;
+    let mut failed = None;
+    for status in &[200, 404, 200] {
+        try_break!(Response { status: *status }, |err: Error| failed = Some(err));
+        seen += 1;
+    }
+    assert_eq!(seen, 1);
+    assert_eq!(failed, Some(Error(404)));
+}
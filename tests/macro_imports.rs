@@ -0,0 +1,27 @@
+extern crate carrier;
+
+use carrier::{first_ok, try_block};
+
+
+fn first_char(cache: Option<char>, s: &str) -> Option<char> {
+    Some(first_ok!(cache, s.chars().next()))
+}
+
+#[test]
+fn test_first_ok_imported_by_path() {
+    assert_eq!(first_char(None, "xy"), Some('x'));
+    assert_eq!(first_char(Some('a'), "xy"), Some('a'));
+    assert_eq!(first_char(None, ""), None);
+}
+
+#[test]
+fn test_first_ok_imported_by_path_in_try_block() {
+    let load = |cache: Option<i32>, disk: &str| -> (Option<i32>, bool) {
+        let rv = try_block! {
+            first_ok!(cache, disk.parse::<i32>().ok())
+        };
+        (rv, true)
+    };
+    assert_eq!(load(None, "42"), (Some(42), true));
+    assert_eq!(load(None, "x"), (None, true));
+}
@@ -0,0 +1,118 @@
+#[macro_use]
+extern crate carrier;
+
+use std::convert::Infallible;
+use std::num::ParseIntError;
+
+use carrier::{Branch, Completion, NoneError};
+
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+    Parse(ParseIntError),
+    Missing(&'static str),
+    Status(i32),
+}
+
+#[derive(Debug, PartialEq)]
+struct AppError(MyError);
+
+impl From<MyError> for AppError {
+    fn from(err: MyError) -> AppError { AppError(err) }
+}
+
+#[test]
+fn test_map_result_error() {
+    fn parse(s: &str) -> Result<i32, MyError> {
+        Ok(try!(s.parse::<i32>() => MyError::Parse))
+    }
+    assert_eq!(parse("42"), Ok(42));
+    assert!(matches!(parse("x"), Err(MyError::Parse(_))));
+}
+
+#[test]
+fn test_map_error_then_into() {
+    fn parse(s: &str) -> Result<i32, AppError> {
+        Ok(try!(s.parse::<i32>() => MyError::Parse))
+    }
+    assert_eq!(parse("42"), Ok(42));
+    assert!(matches!(parse("x"), Err(AppError(MyError::Parse(_)))));
+}
+
+#[test]
+fn test_map_closure() {
+    fn parse(s: &str) -> Result<i32, String> {
+        Ok(try!(s.parse::<i32>() => |err| format!("{}: {}", s, err)))
+    }
+    assert_eq!(parse("x"), Err("x: invalid digit found in string".to_string()));
+}
+
+#[test]
+fn test_map_option_none_error() {
+    fn first(items: &[i32]) -> Result<i32, MyError> {
+        Ok(*try!(items.first() => |_: NoneError| MyError::Missing("first")))
+    }
+    assert_eq!(first(&[42]), Ok(42));
+    assert_eq!(first(&[]), Err(MyError::Missing("first")));
+}
+
+#[test]
+fn test_else_option() {
+    fn first(items: &[i32]) -> Result<i32, AppError> {
+        Ok(*try!(items.first() else || MyError::Missing("first")))
+    }
+    assert_eq!(first(&[42]), Ok(42));
+    assert_eq!(first(&[]), Err(AppError(MyError::Missing("first"))));
+}
+
+#[test]
+fn test_else_raw_ptr() {
+    fn deref(ptr: *const i32) -> Result<i32, MyError> {
+        unsafe { Ok(*try!(ptr else || MyError::Missing("ptr"))) }
+    }
+    let value = 42;
+    assert_eq!(deref(&value), Ok(42));
+    assert_eq!(deref(std::ptr::null()), Err(MyError::Missing("ptr")));
+}
+
+#[test]
+fn test_else_with_if_expression() {
+    fn pick(flag: bool, items: &[i32]) -> Result<i32, MyError> {
+        Ok(*try!(if flag { items.first() } else { items.last() }
+                 else || MyError::Missing("item")))
+    }
+    assert_eq!(pick(true, &[1, 2]), Ok(1));
+    assert_eq!(pick(false, &[1, 2]), Ok(2));
+    assert_eq!(pick(false, &[]), Err(MyError::Missing("item")));
+}
+
+#[test]
+fn test_plain_if_else_expression() {
+    fn pick(flag: bool) -> Option<i32> {
+        Some(try!(if flag { Some(1) } else { None }))
+    }
+    assert_eq!(pick(true), Some(1));
+    assert_eq!(pick(false), None);
+}
+
+#[test]
+fn test_map_custom_branch() {
+    struct Response {
+        status: i32,
+    }
+
+    impl Branch for Response {
+        type Value = Response;
+        type Residual = Result<Infallible, i32>;
+
+        fn branch(self) -> Completion<Response, Result<Infallible, i32>> {
+            if self.status == 200 { Completion::Value(self) } else { Completion::Abrupt(Err(self.status)) }
+        }
+    }
+
+    fn http_test(status: i32) -> Result<i32, AppError> {
+        Ok(try!(Response { status } => MyError::Status).status)
+    }
+    assert_eq!(http_test(200), Ok(200));
+    assert_eq!(http_test(404), Err(AppError(MyError::Status(404))));
+}
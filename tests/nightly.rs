@@ -0,0 +1,54 @@
+#![cfg(feature = "nightly")]
+
+extern crate carrier;
+
+mod common;
+
+use carrier::{Completion, IntoCompletion};
+
+use common::{Error, Response};
+
+
+#[test]
+fn test_question_mark_on_completion() {
+    fn half(x: i32) -> Completion<i32, &'static str> {
+        if x % 2 == 0 { Completion::Value(x / 2) } else { Completion::Abrupt("odd") }
+    }
+    fn quarter(x: i32) -> Completion<i32, String> {
+        let x = half(x)?;
+        Completion::Value(half(x)?)
+    }
+    assert_eq!(quarter(168), Completion::Value(42));
+    assert_eq!(quarter(6), Completion::Abrupt("odd".to_string()));
+}
+
+#[test]
+fn test_question_mark_on_custom_carrier() {
+    fn http_test(status: i32) -> Result<i32, Error> {
+        let resp = Response { status }.into_completion()?;
+        Ok(resp.status)
+    }
+    assert_eq!(http_test(404), Err(Error(404)));
+    assert_eq!(http_test(200), Ok(200));
+}
+
+#[test]
+fn test_question_mark_matches_macro_conversions() {
+    #[derive(PartialEq, Debug)]
+    struct MyError;
+
+    impl From<()> for MyError {
+        fn from(_err: ()) -> MyError { MyError }
+    }
+
+    fn next() -> Option<Result<i32, MyError>> {
+        let x = Err::<i32, ()>(()).into_completion()?;
+        Some(Ok(x))
+    }
+    fn none() -> Option<String> {
+        let x = None::<i32>.into_completion()?;
+        Some(x.to_string())
+    }
+    assert_eq!(next(), Some(Err(MyError)));
+    assert_eq!(none(), None);
+}
@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate carrier;
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use carrier::NoneError;
+
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+    Missing,
+}
+
+impl From<NoneError> for MyError {
+    fn from(_err: NoneError) -> MyError { MyError::Missing }
+}
+
+#[test]
+fn test_option_into_result() {
+    fn lookup(map: &HashMap<&str, i32>, key: &str) -> Result<i32, MyError> {
+        Ok(*try!(map.get(key)) * 2)
+    }
+    let mut map = HashMap::new();
+    map.insert("a", 21);
+    assert_eq!(lookup(&map, "a"), Ok(42));
+    assert_eq!(lookup(&map, "b"), Err(MyError::Missing));
+}
+
+#[test]
+fn test_option_into_none_error() {
+    fn first(items: &[i32]) -> Result<i32, NoneError> {
+        Ok(*try!(items.first()))
+    }
+    assert_eq!(first(&[42]), Ok(42));
+    assert_eq!(first(&[]), Err(NoneError));
+}
+
+#[test]
+fn test_option_into_boxed_error() {
+    fn first(items: &[i32]) -> Result<i32, Box<dyn Error>> {
+        Ok(*try!(items.first()))
+    }
+    let err = first(&[]).unwrap_err();
+    assert_eq!(err.to_string(), "expected a value but found none");
+    assert!(err.downcast_ref::<NoneError>().is_some());
+}
+
+#[test]
+fn test_null_ptr_into_result() {
+    fn deref(ptr: *const i32) -> Result<i32, MyError> {
+        unsafe { Ok(*try!(ptr)) }
+    }
+    let value = 42;
+    assert_eq!(deref(&value), Ok(42));
+    assert_eq!(deref(std::ptr::null()), Err(MyError::Missing));
+}
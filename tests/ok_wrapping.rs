@@ -0,0 +1,74 @@
+#[macro_use]
+extern crate carrier;
+
+use std::task::Poll;
+
+use carrier::Completion;
+
+
+#[test]
+fn test_ok_result() {
+    fn foo() -> Result<i32, ()> {
+        ok!(42)
+    }
+    assert_eq!(foo(), Ok(42));
+}
+
+#[test]
+fn test_ok_unit() {
+    fn foo() -> Result<(), ()> {
+        ok!()
+    }
+    assert_eq!(foo(), Ok(()));
+}
+
+#[test]
+fn test_ok_option() {
+    fn foo() -> Option<i32> {
+        ok!(42)
+    }
+    assert_eq!(foo(), Some(42));
+}
+
+#[test]
+fn test_ok_option_result() {
+    fn bar() -> Result<i32, ()> {
+        Ok(21)
+    }
+    fn next() -> Option<Result<i32, ()>> {
+        ok!(try!(bar()) * 2)
+    }
+    assert_eq!(next(), Some(Ok(42)));
+}
+
+#[test]
+fn test_ok_option_of_result_value() {
+    fn next() -> Option<Result<i32, ()>> {
+        ok!(Ok(42))
+    }
+    assert_eq!(next(), Some(Ok(42)));
+}
+
+#[test]
+fn test_ok_poll() {
+    fn poll() -> Poll<i32> {
+        ok!(42)
+    }
+    fn poll_result() -> Poll<Result<i32, ()>> {
+        ok!(42)
+    }
+    fn poll_next() -> Poll<Option<Result<i32, ()>>> {
+        ok!(42)
+    }
+    assert_eq!(poll(), Poll::Ready(42));
+    assert_eq!(poll_result(), Poll::Ready(Ok(42)));
+    assert_eq!(poll_next(), Poll::Ready(Some(Ok(42))));
+}
+
+#[test]
+fn test_ok_completion() {
+    fn foo() -> Completion<i32, ()> {
+        ok!(42)
+    }
+    assert_eq!(foo(), Completion::Value(42));
+}
@@ -0,0 +1,134 @@
+#[macro_use]
+extern crate carrier;
+
+use std::num::ParseIntError;
+
+use carrier::{NoneError, Outcome};
+
+
+#[derive(Debug, PartialEq)]
+enum ConfigError {
+    BadNumber,
+    Missing,
+}
+
+impl From<ParseIntError> for ConfigError {
+    fn from(_err: ParseIntError) -> ConfigError { ConfigError::BadNumber }
+}
+
+impl From<NoneError> for ConfigError {
+    fn from(_err: NoneError) -> ConfigError { ConfigError::Missing }
+}
+
+type Loaded<T> = Outcome<T, String, ConfigError>;
+
+fn read_key(key: &str, raw: &str) -> Loaded<i32> {
+    outcome! {
+        let value = try!(raw.parse::<i32>());
+        if key.starts_with("old_") {
+            try!(Outcome::<(), String, ConfigError>::new(())
+                 .with_warning(format!("{} is deprecated", key)));
+        }
+        value
+    }
+}
+
+#[test]
+fn test_outcome_merges_warnings() {
+    fn load() -> Loaded<i32> {
+        outcome! {
+            let a = try!(read_key("old_a", "1"));
+            let b = try!(read_key("b", "2"));
+            let c = try!(read_key("old_c", "3"));
+            a + b + c
+        }
+    }
+    let (result, warnings) = load().into_parts();
+    assert_eq!(result, Ok(6));
+    assert_eq!(warnings, vec!["old_a is deprecated", "old_c is deprecated"]);
+}
+
+#[test]
+fn test_outcome_fatal_keeps_warnings() {
+    fn load() -> Loaded<i32> {
+        outcome! {
+            let a = try!(read_key("old_a", "1"));
+            let b = try!(read_key("b", "x"));
+            let c = try!(read_key("old_c", "3"));
+            a + b + c
+        }
+    }
+    let rv = load();
+    assert!(rv.is_fatal());
+    assert_eq!(rv.warnings(), &["old_a is deprecated".to_string()]);
+    assert_eq!(rv.into_result(), Err(ConfigError::BadNumber));
+}
+
+#[test]
+fn test_outcome_nested_fatal_warnings() {
+    fn inner() -> Loaded<i32> {
+        Outcome::fatal(ConfigError::Missing).with_warning("inner".to_string())
+    }
+    fn outer() -> Loaded<i32> {
+        outcome! {
+            let a = try!(read_key("old_a", "1"));
+            a + try!(inner())
+        }
+    }
+    assert_eq!(outer().warnings(), &["old_a is deprecated".to_string(), "inner".to_string()]);
+}
+
+#[test]
+fn test_outcome_mixes_results_and_options() {
+    fn load(raw: Option<&str>) -> Loaded<i32> {
+        outcome! {
+            let raw = try!(raw);
+            try!(raw.parse::<i32>()) * 2
+        }
+    }
+    assert_eq!(load(Some("21")).into_result(), Ok(42));
+    assert_eq!(load(Some("x")).into_result(), Err(ConfigError::BadNumber));
+    assert_eq!(load(None).into_result(), Err(ConfigError::Missing));
+}
+
+#[test]
+fn test_outcome_labeled_merges_warnings() {
+    fn load() -> Loaded<i32> {
+        outcome! {
+            try!(#"old_a" read_key("old_a", "1"))
+        }
+    }
+    let (result, warnings) = load().into_parts();
+    assert_eq!(result, Ok(1));
+    assert_eq!(warnings, vec!["old_a is deprecated"]);
+}
+
+#[test]
+fn test_outcome_in_result_fn() {
+    fn load() -> Result<i32, ConfigError> {
+        Ok(try!(read_key("old_a", "x").into_result()))
+    }
+    assert_eq!(load(), Err(ConfigError::BadNumber));
+}
+
+#[test]
+fn test_outcome_return_leaves_function() {
+    fn load(early: bool) -> Loaded<i32> {
+        if early {
+            return Outcome::new(0).with_warning("early".to_string());
+        }
+        outcome! {
+            try!(read_key("old_a", "1"))
+        }
+    }
+    assert_eq!(load(true).warnings(), &["early".to_string()]);
+    assert_eq!(load(false).into_result(), Ok(1));
+}
+
+#[test]
+fn test_outcome_map() {
+    let rv: Outcome<i32, &str, ()> = Outcome::new(21).with_warning("w");
+    let rv = rv.map(|x| x * 2);
+    assert_eq!(rv.result(), Ok(&42));
+    assert_eq!(rv.warnings(), &["w"]);
+}
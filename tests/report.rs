@@ -0,0 +1,112 @@
+#[macro_use]
+extern crate carrier;
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::num::ParseIntError;
+
+use carrier::report::Report;
+use carrier::{Context, Error};
+
+
+#[derive(Debug)]
+struct Quoted(&'static str);
+
+impl fmt::Display for Quoted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad \"{}\"\n\tvalue", self.0)
+    }
+}
+
+impl StdError for Quoted {}
+
+fn load(s: &str) -> Result<i32, Context<ParseIntError>> {
+    Ok(try!(s.parse::<i32>(); "parsing {}", s))
+}
+
+fn load_config(s: &str) -> Result<i32, Context<ParseIntError>> {
+    Ok(try!(load(s); "loading config"))
+}
+
+#[test]
+fn test_report_text() {
+    let err = load_config("x").unwrap_err();
+    assert_eq!(Report::new(&err).to_string(), "\
+Error: loading config
+
+Caused by:
+    0: parsing x
+    1: invalid digit found in string");
+}
+
+#[test]
+fn test_report_no_causes() {
+    let err = "x".parse::<i32>().unwrap_err();
+    assert_eq!(Report::new(&err).to_string(), "Error: invalid digit found in string");
+    assert_eq!(Report::new(&err).causes().count(), 0);
+}
+
+#[test]
+fn test_report_json() {
+    let err = load_config("x").unwrap_err();
+    assert_eq!(Report::new(&err).to_json(), "\
+{\"error\":\"loading config\",\
+\"causes\":[\"parsing x\",\"invalid digit found in string\"],\
+\"locations\":[]}");
+}
+
+#[test]
+fn test_report_json_escaping() {
+    let err = Quoted("x");
+    assert_eq!(Report::new(&err).to_json(),
+               "{\"error\":\"bad \\\"x\\\"\\n\\tvalue\",\"causes\":[],\"locations\":[]}");
+}
+
+#[test]
+fn test_report_carrier_error() {
+    fn run() -> Result<i32, Error> {
+        Ok(try!(load("x")))
+    }
+    let err = run().unwrap_err();
+    let report = Report::from(&err);
+    assert_eq!(report.to_string(), "\
+Error: parsing x
+
+Caused by:
+    0: invalid digit found in string");
+    assert_eq!(report.to_string(), Report::from(&err).to_string());
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn test_report_traced() {
+    use carrier::trace::Traced;
+
+    const PARSE_LINE: u32 = line!() + 2;
+    fn parse(s: &str) -> Result<i32, Traced<Context<ParseIntError>>> {
+        Ok(try!(load(s)))
+    }
+
+    let err = parse("x").unwrap_err();
+    let report = Report::from(&err);
+    assert_eq!(report.locations().len(), 1);
+    let site = report.locations()[0];
+    assert_eq!(site.line(), PARSE_LINE);
+    let location = match site.expression() {
+        Some(expr) => format!("`{}` failed at {}", expr, site),
+        None => format!("report at {}", site),
+    };
+    assert_eq!(report.to_string(), format!("\
+Error: parsing x
+
+Caused by:
+    0: invalid digit found in string
+
+Locations:
+    0: {}", location));
+    assert!(report.to_json().ends_with(&format!(
+        "\"locations\":[{{\"file\":\"tests/report.rs\",\"line\":{},\"column\":12,\
+         \"module_path\":\"report\",\"expression\":{},\"label\":null}}]}}",
+        PARSE_LINE,
+        site.expression().map_or("null".to_string(), |x| format!("\"{}\"", x)))));
+}
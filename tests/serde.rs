@@ -0,0 +1,23 @@
+#![cfg(feature = "serde")]
+
+extern crate carrier;
+extern crate serde_json;
+
+use carrier::Completion;
+
+
+#[test]
+fn test_serialize() {
+    let v: Completion<i32, String> = Completion::Value(42);
+    let a: Completion<i32, String> = Completion::Abrupt("fail".to_string());
+    assert_eq!(serde_json::to_string(&v).unwrap(), r#"{"value":42}"#);
+    assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"abrupt":"fail"}"#);
+}
+
+#[test]
+fn test_deserialize() {
+    let v: Completion<i32, String> = serde_json::from_str(r#"{"value":42}"#).unwrap();
+    let a: Completion<i32, String> = serde_json::from_str(r#"{"abrupt":"fail"}"#).unwrap();
+    assert_eq!(v, Completion::Value(42));
+    assert_eq!(a, Completion::Abrupt("fail".to_string()));
+}
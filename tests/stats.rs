@@ -0,0 +1,74 @@
+#![cfg(feature = "stats")]
+#[macro_use]
+extern crate carrier;
+
+use std::num::ParseIntError;
+
+use carrier::stats;
+
+
+const PARSE_LINE: u32 = line!() + 2;
+fn parse(s: &str) -> Result<i32, ParseIntError> {
+    Ok(try!(s.parse::<i32>()))
+}
+
+fn parse_opt(s: &str) -> Option<i32> {
+    Some(try!(s.parse::<i32>().ok()))
+}
+
+fn find(snapshot: &stats::Snapshot, line: u32) -> stats::SiteStats {
+    *snapshot.sites().iter()
+        .find(|stats| stats.site().file() == "tests/stats.rs" && stats.site().line() == line)
+        .expect("site not registered")
+}
+
+#[test]
+fn test_stats_counts() {
+    parse("1").ok();
+    parse("2").ok();
+    parse("x").ok();
+    let snapshot = stats::snapshot();
+    let site = find(&snapshot, PARSE_LINE);
+    assert_eq!(site.values(), 2);
+    assert_eq!(site.abrupts(), 1);
+    assert_eq!(site.total(), 3);
+    assert_eq!(snapshot.get(site.site()), Some(&site));
+}
+
+#[test]
+fn test_stats_sorting() {
+    parse_opt("x");
+    parse_opt("y");
+    let mut snapshot = stats::snapshot();
+    snapshot.sort_by_abrupts();
+    let abrupts: Vec<_> = snapshot.sites().iter().map(|x| x.abrupts()).collect();
+    let mut sorted = abrupts.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+    assert_eq!(abrupts, sorted);
+
+    snapshot.sort_by_site();
+    let sites: Vec<_> = snapshot.sites().iter().map(|x| *x.site()).collect();
+    let mut sorted = sites.clone();
+    sorted.sort();
+    assert_eq!(sites, sorted);
+}
+
+#[test]
+fn test_stats_export() {
+    const FAIL_LINE: u32 = line!() + 2;
+    fn fail() -> Result<(), &'static str> {
+        try!(Err("nope"));
+        Ok(())
+    }
+    fail().ok();
+    let mut snapshot = stats::snapshot();
+    snapshot.retain(|stats| stats.site().line() == FAIL_LINE);
+    assert_eq!(snapshot.to_json(), format!(
+        "[{{\"file\":\"tests/stats.rs\",\"line\":{},\"column\":9,\
+         \"module_path\":\"stats\",\
+         \"expression\":{},\"label\":null,\"values\":0,\"abrupts\":1}}]",
+        FAIL_LINE,
+        if cfg!(feature = "trace-expr") { "\"Err(\\\"nope\\\")\"" } else { "null" }));
+    assert_eq!(snapshot.to_string(), format!(
+        "    values    abrupts  site\n         0          1  tests/stats.rs:{}:9", FAIL_LINE));
+}
@@ -0,0 +1,206 @@
+#![cfg(feature = "trace")]
+#[macro_use]
+extern crate carrier;
+
+use std::num::ParseIntError;
+use std::thread;
+
+use carrier::trace::{Trace, Traced};
+
+
+const PARSE_LINE: u32 = line!() + 2;
+fn parse(s: &str) -> Result<i32, Traced<ParseIntError>> {
+    Ok(try!(s.parse::<i32>()))
+}
+
+const PARSE_TWICE_LINE: u32 = line!() + 2;
+fn parse_twice(s: &str) -> Result<i32, Traced<ParseIntError>> {
+    Ok(try!(parse(s)) * 2)
+}
+
+fn lines(err: &Traced<ParseIntError>) -> Vec<u32> {
+    err.trace().frames().iter().map(|site| site.line()).collect()
+}
+
+#[test]
+fn test_trace_records_frames() {
+    let err = parse_twice("x").unwrap_err();
+    let frames = err.trace().frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].file(), "tests/trace.rs");
+    assert_eq!(frames[0].line(), PARSE_LINE);
+    assert_eq!(frames[1].line(), PARSE_TWICE_LINE);
+    assert_eq!(frames[1].module_path(), "trace");
+}
+
+#[test]
+fn test_trace_value_is_untouched() {
+    assert_eq!(parse_twice("21").unwrap(), 42);
+}
+
+#[test]
+#[cfg(not(feature = "trace-expr"))]
+fn test_trace_display() {
+    let err = parse_twice("x").unwrap_err();
+    assert_eq!(err.to_string(), "invalid digit found in string");
+    assert_eq!(err.trace().to_string(), format!(
+        "   0: trace at {}\n   1: trace at {}",
+        err.trace().frames()[0], err.trace().frames()[1]));
+    assert_eq!(err.trace().frames()[0].to_string(), format!("tests/trace.rs:{}:8", PARSE_LINE));
+}
+
+#[test]
+fn test_trace_untraced_error() {
+    fn parse(s: &str) -> Result<i32, ParseIntError> {
+        Ok(try!(s.parse::<i32>()))
+    }
+    assert!(parse("x").is_err());
+}
+
+#[test]
+fn test_trace_option_result() {
+    fn parse(s: &str) -> Option<Result<i32, Traced<ParseIntError>>> {
+        Some(Ok(try!(s.parse::<i32>())))
+    }
+    let err = parse("x").unwrap().unwrap_err();
+    assert_eq!(err.trace().frames().len(), 1);
+}
+
+#[test]
+fn test_trace_try_block() {
+    let rv: Result<i32, Traced<ParseIntError>> = try_block! {
+        try!(parse("x"))
+    };
+    assert_eq!(rv.unwrap_err().trace().frames().len(), 2);
+}
+
+#[test]
+fn test_trace_into_error() {
+    let err = parse_twice("x").unwrap_err();
+    let trace: Trace = err.trace().clone();
+    assert!(!trace.is_empty());
+    assert_eq!(err.into_error(), "x".parse::<i32>().unwrap_err());
+}
+
+#[test]
+fn test_trace_closure() {
+    let parse = |s: &str| {
+        let x = try!(s.parse::<i32>());
+        Ok::<_, ParseIntError>(x)
+    };
+    assert_eq!(parse("42"), Ok(42));
+    assert!(parse("x").is_err());
+
+    let line = line!() + 2;
+    let parse_traced = |s: &str| {
+        let x = try!(parse(s));
+        Ok::<_, Traced<ParseIntError>>(x)
+    };
+    let err = parse_traced("x").unwrap_err();
+    assert_eq!(lines(&err), vec![line]);
+}
+
+#[test]
+fn test_trace_handled_error_is_not_recorded() {
+    fn recover(s: &str) -> Result<i32, Traced<ParseIntError>> {
+        Ok(parse(s).unwrap_or(0))
+    }
+    const SECOND_LINE: u32 = line!() + 3;
+    fn parse_or_fail(s: &str) -> Result<i32, Traced<ParseIntError>> {
+        let first = try!(recover(s));
+        let second = try!(parse("y"));
+        Ok(first + second)
+    }
+    let err = parse_or_fail("x").unwrap_err();
+    assert_eq!(lines(&err), vec![PARSE_LINE, SECOND_LINE]);
+}
+
+#[test]
+fn test_trace_stored_error() {
+    const LINE: u32 = line!() + 4;
+    fn parse_later(s: &str) -> Result<i32, Traced<ParseIntError>> {
+        let rv = parse(s);
+        let other = "42".parse::<i32>().unwrap();
+        Ok(try!(rv) + other)
+    }
+    let err = parse_later("x").unwrap_err();
+    assert_eq!(lines(&err), vec![PARSE_LINE, LINE]);
+}
+
+#[test]
+fn test_trace_older_error() {
+    const LINE: u32 = line!() + 4;
+    fn parse_first(a: &str, b: &str) -> Result<i32, Traced<ParseIntError>> {
+        let first = parse(a);
+        let second = parse(b);
+        let x = try!(first);
+        Ok(x + second.unwrap_or(0))
+    }
+    let err = parse_first("x", "y").unwrap_err();
+    assert_eq!(lines(&err), vec![PARSE_LINE, LINE]);
+}
+
+#[test]
+fn test_trace_error_from_other_thread() {
+    const LINE: u32 = line!() + 3;
+    fn parse_in_thread(s: &'static str) -> Result<i32, Traced<ParseIntError>> {
+        let rv = thread::spawn(move || parse(s)).join().unwrap();
+        Ok(try!(rv))
+    }
+    let err = parse_in_thread("x").unwrap_err();
+    assert_eq!(lines(&err), vec![PARSE_LINE, LINE]);
+}
+
+#[test]
+fn test_trace_wrapped_without_try() {
+    const LINE: u32 = line!() + 2;
+    fn fail() -> Result<i32, Traced<ParseIntError>> {
+        try!(Err(Traced::new("x".parse::<i32>().unwrap_err())));
+        Ok(0)
+    }
+    let err = fail().unwrap_err();
+    assert_eq!(lines(&err), vec![LINE]);
+    assert!(Traced::new(42).trace().is_empty());
+}
+
+#[test]
+fn test_trace_keeps_recording_after_inspection() {
+    fn parse_and_inspect(s: &str) -> Result<i32, Traced<ParseIntError>> {
+        let rv = parse(s);
+        if let Err(ref err) = rv {
+            assert_eq!(err.trace().frames().len(), 1);
+        }
+        Ok(try!(rv))
+    }
+    let err = parse_and_inspect("x").unwrap_err();
+    assert_eq!(err.trace().frames().len(), 2);
+}
+
+#[test]
+fn test_trace_clones_are_independent() {
+    fn relay(rv: Result<i32, Traced<ParseIntError>>) -> Result<i32, Traced<ParseIntError>> {
+        Ok(try!(rv))
+    }
+    let err = parse("x").unwrap_err();
+    let copy = err.clone();
+    let err = relay(Err(err)).unwrap_err();
+    assert_eq!(err.trace().frames().len(), 2);
+    assert_eq!(copy.trace().frames().len(), 1);
+}
+
+#[test]
+fn test_trace_inferred_types() {
+    fn parse_inferred(s: &str) -> Result<i32, ParseIntError> {
+        Ok(try!(s.parse()))
+    }
+    fn parse_traced_inferred(s: &str) -> Result<i32, Traced<ParseIntError>> {
+        Ok(try!(s.parse()))
+    }
+    let parse_closure = |s: &str| {
+        let x: i32 = try!(s.parse());
+        Ok::<_, ParseIntError>(x)
+    };
+    assert_eq!(parse_inferred("42"), Ok(42));
+    assert_eq!(parse_traced_inferred("x").unwrap_err().trace().frames().len(), 1);
+    assert_eq!(parse_closure("42"), Ok(42));
+}
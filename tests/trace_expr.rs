@@ -0,0 +1,52 @@
+#![cfg(feature = "trace-expr")]
+#[macro_use]
+extern crate carrier;
+
+use std::num::ParseIntError;
+
+use carrier::trace::Traced;
+
+
+const PARSE_LINE: u32 = line!() + 2;
+fn parse(s: &str) -> Result<i32, Traced<ParseIntError>> {
+    Ok(try!(s.parse::<i32>()))
+}
+
+const PARSE_PAIR_LINE: u32 = line!() + 2;
+fn parse_pair(a: &str, b: &str) -> Result<i32, Traced<ParseIntError>> {
+    Ok(try!(parse(a)) + try!(parse(b)))
+}
+
+#[test]
+fn test_trace_expr_records_expression() {
+    let err = parse_pair("1", "x").unwrap_err();
+    let frames = err.trace().frames();
+    assert_eq!(frames[0].expression(), Some("s.parse::<i32>()"));
+    assert_eq!(frames[1].expression(), Some("parse(b)"));
+}
+
+#[test]
+fn test_trace_expr_display() {
+    let err = parse_pair("x", "1").unwrap_err();
+    assert_eq!(err.trace().to_string(), format!("   \
+        0: `s.parse::<i32>()` failed at tests/trace_expr.rs:{}:8\n   \
+        1: `parse(a)` failed at tests/trace_expr.rs:{}:8", PARSE_LINE, PARSE_PAIR_LINE));
+}
+
+#[test]
+fn test_trace_expr_map_form() {
+    fn parse(s: &str) -> Result<i32, Traced<String>> {
+        Ok(try!(s.parse::<i32>() => |err| err.to_string()))
+    }
+    let err = parse("x").unwrap_err();
+    assert_eq!(err.trace().frames()[0].expression(), Some("s.parse::<i32>()"));
+}
+
+#[test]
+fn test_trace_expr_else_form() {
+    fn parse(s: &str) -> Result<i32, Traced<&'static str>> {
+        Ok(try!(s.parse::<i32>() else || "bad number"))
+    }
+    let err = parse("x").unwrap_err();
+    assert_eq!(err.trace().frames()[0].expression(), Some("s.parse::<i32>()"));
+}
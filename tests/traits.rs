@@ -0,0 +1,43 @@
+extern crate carrier;
+
+use std::collections::HashMap;
+
+use carrier::Completion;
+
+
+#[test]
+fn test_debug() {
+    let v: Completion<i32, ()> = Completion::Value(42);
+    let a: Completion<i32, &str> = Completion::Abrupt("fail");
+    assert_eq!(format!("{:?}", v), "Value(42)");
+    assert_eq!(format!("{:?}", a), "Abrupt(\"fail\")");
+}
+
+#[test]
+fn test_clone_copy() {
+    let v: Completion<String, ()> = Completion::Value("42".to_string());
+    assert_eq!(v.clone(), v);
+    let c: Completion<i32, ()> = Completion::Value(42);
+    let d = c;
+    assert_eq!(c, d);
+}
+
+#[test]
+fn test_ord() {
+    let v: Completion<i32, i32> = Completion::Value(100);
+    let a: Completion<i32, i32> = Completion::Abrupt(1);
+    assert!(v < a);
+    assert!(Completion::<i32, i32>::Value(1) < v);
+    let mut items = vec![a, v, Completion::Value(1)];
+    items.sort();
+    assert_eq!(items, vec![Completion::Value(1), v, a]);
+}
+
+#[test]
+fn test_hash() {
+    let mut map = HashMap::new();
+    map.insert(Completion::<i32, &str>::Value(42), "value");
+    map.insert(Completion::Abrupt("fail"), "abrupt");
+    assert_eq!(map[&Completion::Value(42)], "value");
+    assert_eq!(map[&Completion::Abrupt("fail")], "abrupt");
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate carrier;
+
+use std::num::ParseIntError;
+
+
+#[test]
+fn test_try_block_value() {
+    let rv: Result<i32, ParseIntError> = try_block! {
+        let a = try!("20".parse::<i32>());
+        let b = try!("22".parse::<i32>());
+        a + b
+    };
+    assert_eq!(rv, Ok(42));
+}
+
+#[test]
+fn test_try_block_abrupt() {
+    let rv: Result<i32, ParseIntError> = try_block! {
+        let a = try!("20".parse::<i32>());
+        let b = try!("x".parse::<i32>());
+        a + b
+    };
+    assert!(rv.is_err());
+}
+
+#[test]
+fn test_try_block_does_not_return_from_function() {
+    fn parse_all(items: &[&str]) -> (Vec<i32>, usize) {
+        let mut values = vec![];
+        let mut failed = 0;
+        for item in items {
+            let rv: Option<i32> = try_block! {
+                let x = try!(item.parse::<i32>().ok());
+                x * 2
+            };
+            match rv {
+                Some(x) => values.push(x),
+                None => failed += 1,
+            }
+        }
+        (values, failed)
+    }
+    assert_eq!(parse_all(&["1", "x", "21"]), (vec![2, 42], 1));
+}
+
+#[test]
+fn test_try_block_converts_error() {
+    #[derive(Debug, PartialEq)]
+    struct MyError;
+
+    impl From<ParseIntError> for MyError {
+        fn from(_err: ParseIntError) -> MyError { MyError }
+    }
+
+    let rv: Result<i32, MyError> = try_block! {
+        try!("x".parse::<i32>())
+    };
+    assert_eq!(rv, Err(MyError));
+}
+
+#[test]
+fn test_try_block_option_result() {
+    let rv: Option<Result<i32, ParseIntError>> = try_block! {
+        try!("42".parse::<i32>())
+    };
+    assert_eq!(rv, Some(Ok(42)));
+}
@@ -0,0 +1,105 @@
+#[macro_use]
+extern crate carrier;
+
+use std::num::ParseIntError;
+
+use carrier::validated::Errors;
+use carrier::{NoneError, Validated};
+
+
+#[derive(Debug, PartialEq)]
+enum FieldError {
+    BadNumber(String),
+    Missing,
+}
+
+impl From<ParseIntError> for FieldError {
+    fn from(err: ParseIntError) -> FieldError { FieldError::BadNumber(err.to_string()) }
+}
+
+impl From<NoneError> for FieldError {
+    fn from(_err: NoneError) -> FieldError { FieldError::Missing }
+}
+
+fn parse_form(name: Option<&str>, age: &str, height: &str)
+    -> Validated<(String, i32, i32), FieldError>
+{
+    validate!(name.map(|x| x.to_string()), age.parse::<i32>(), height.parse::<i32>())
+}
+
+#[test]
+fn test_validate_all_valid() {
+    assert_eq!(parse_form(Some("John"), "42", "180").valid(),
+               Some(("John".to_string(), 42, 180)));
+}
+
+#[test]
+fn test_validate_collects_all_errors() {
+    let rv = parse_form(None, "x", "180");
+    assert!(rv.is_invalid());
+    assert_eq!(rv.into_result(), Err(vec![
+        FieldError::Missing,
+        FieldError::BadNumber("invalid digit found in string".to_string()),
+    ]));
+}
+
+#[test]
+fn test_validated_with_try() {
+    fn check(age: &str) -> Result<i32, Vec<FieldError>> {
+        let form: Validated<(i32,), FieldError> = validate!(age.parse::<i32>());
+        let (age,) = try!(form);
+        Ok(age)
+    }
+    assert_eq!(check("42"), Ok(42));
+    assert_eq!(check("x").unwrap_err().len(), 1);
+}
+
+#[test]
+fn test_try_in_validated_fn() {
+    fn parse(s: &str) -> Validated<i32, FieldError> {
+        let value = try!(s.parse::<i32>());
+        ok!(value * 2)
+    }
+    assert_eq!(parse("21"), Validated::Valid(42));
+    assert_eq!(parse("x").errors().map(|x| x.len()), Some(1));
+}
+
+#[test]
+fn test_validated_and() {
+    let a: Validated<i32, &str> = Validated::Invalid(Errors::new("a"));
+    let b: Validated<i32, &str> = Validated::Invalid(Errors::new("b"));
+    let c: Validated<i32, &str> = Validated::Valid(1);
+    assert_eq!(a.and(b).into_result(), Err(vec!["a", "b"]));
+    assert_eq!(c.clone().and(c).valid(), Some((1, 1)));
+}
+
+#[test]
+fn test_validated_collect() {
+    let rv: Validated<Vec<i32>, FieldError> = ["1", "x", "3", "y"].iter()
+        .map(|x| Validated::from_source(x.parse::<i32>()))
+        .collect();
+    assert_eq!(rv.errors().map(|x| x.len()), Some(2));
+    let rv: Validated<Vec<i32>, FieldError> = ["1", "2"].iter()
+        .map(|x| Validated::from_source(x.parse::<i32>()))
+        .collect();
+    assert_eq!(rv.valid(), Some(vec![1, 2]));
+}
+
+#[test]
+fn test_errors() {
+    let mut errors = Errors::new(1);
+    errors.push(2);
+    errors.append(Errors::from_vec(vec![3, 4]).unwrap());
+    assert_eq!(errors.len(), 4);
+    assert_eq!(*errors.first(), 1);
+    assert_eq!(errors.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    assert_eq!(errors.map(|x| x * 2).into_vec(), vec![2, 4, 6, 8]);
+    assert!(Errors::<i32>::from_vec(vec![]).is_none());
+}
+
+#[test]
+fn test_validated_map_errors() {
+    let rv: Validated<i32, i32> = Validated::Invalid(Errors::new(1));
+    assert_eq!(rv.map(|x| x + 1).map_errors(|x| x.to_string()).into_result(),
+               Err(vec!["1".to_string()]));
+}